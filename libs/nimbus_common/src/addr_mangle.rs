@@ -0,0 +1,56 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Compact binary form of a `SocketAddr`, used by the `socket_addr` fields
+/// of the rendezvous protobuf messages.
+///
+/// - ipv4: 4 bytes ip + 2 bytes port (big endian)
+/// - ipv6: 16 bytes ip + 2 bytes port (big endian)
+pub struct AddrMangle();
+
+impl AddrMangle {
+  pub fn encode(addr: SocketAddr) -> Vec<u8> {
+    let mut bytes = match addr.ip() {
+      IpAddr::V4(ip) => ip.octets().to_vec(),
+      IpAddr::V6(ip) => ip.octets().to_vec(),
+    };
+    bytes.extend(addr.port().to_be_bytes());
+    bytes
+  }
+
+  pub fn decode(bytes: &[u8]) -> Option<SocketAddr> {
+    let (ip, port) = match bytes.len() {
+      6 => {
+        let mut ip = [0u8; 4];
+        ip.copy_from_slice(&bytes[..4]);
+        (IpAddr::V4(Ipv4Addr::from(ip)), &bytes[4..])
+      }
+      18 => {
+        let mut ip = [0u8; 16];
+        ip.copy_from_slice(&bytes[..16]);
+        (IpAddr::V6(Ipv6Addr::from(ip)), &bytes[16..])
+      }
+      _ => return None,
+    };
+    Some(SocketAddr::new(ip, u16::from_be_bytes([port[0], port[1]])))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_mangle() {
+    let addr: SocketAddr = "192.168.1.2:8080".parse().unwrap();
+    assert_eq!(AddrMangle::encode(addr).len(), 6);
+    assert_eq!(AddrMangle::decode(&AddrMangle::encode(addr)), Some(addr));
+
+    let addr: SocketAddr = "[2001:db8::1]:21116".parse().unwrap();
+    assert_eq!(AddrMangle::encode(addr).len(), 18);
+    assert_eq!(AddrMangle::decode(&AddrMangle::encode(addr)), Some(addr));
+
+    assert_eq!(AddrMangle::decode(&[]), None);
+    assert_eq!(AddrMangle::decode(&[1, 2, 3]), None);
+  }
+}
@@ -15,8 +15,15 @@ pub mod logger_initialize {
   #[macro_export]
   macro_rules! logger_init {
     () => {
-      let _logger =
-        nimbus_common::flexi_logger::Logger::try_with_env_or_str("debug")?
+      nimbus_common::common::logger_initialize::logger_init!("debug");
+    };
+    ($spec: expr) => {
+      nimbus_common::common::logger_initialize::logger_init!(_logger, $spec);
+    };
+    // keep the handle as `$logger` to change the log level at runtime
+    ($logger: ident, $spec: expr) => {
+      let $logger =
+        nimbus_common::flexi_logger::Logger::try_with_env_or_str($spec)?
           .log_to_stdout()
           .set_palette("196;208;7;80;8".to_string())
           .format(nimbus_common::flexi_logger::colored_opt_format)
@@ -15,6 +15,10 @@ pub const COMPRESS_LEVEL: i32 = 3;
 pub const CONNECT_TIMEOUT: u64 = 18_000;
 pub const READ_TIMEOUT: u64 = 18_000;
 pub const SERIAL: i32 = 3;
+/// default port of the relay server
+pub const RELAY_PORT: i32 = 8081;
+/// interval of the `RegisterPeer` heartbeats of the clients (ms)
+pub const REG_INTERVAL: i64 = 12_000;
 
 // global static variable
 static CONFIG: Lazy<Arc<RwLock<Config>>> =
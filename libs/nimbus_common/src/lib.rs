@@ -1,4 +1,5 @@
 // common module
+pub mod addr_mangle;
 pub mod bytes_codec;
 pub mod common;
 pub mod compress;
@@ -15,7 +16,10 @@ pub use env_logger;
 pub use flexi_logger;
 pub use futures;
 pub use log;
+pub use once_cell;
 pub use protobuf;
+pub use regex;
+pub use sodiumoxide;
 pub use tokio;
 pub use tokio_util;
 
@@ -31,7 +35,7 @@ pub fn timeout<T: std::future::Future>(
   ms: u64,
   future: T,
 ) -> tokio::time::Timeout<T> {
-  tokio::time::timeout(std::time::Duration::from_micros(ms), future)
+  tokio::time::timeout(std::time::Duration::from_millis(ms), future)
 }
 
 const IPV4_REGEX_MATCH: &str = r"^(?:25[0-5]|2[0-4][0-9]|[01]?[0-9][0-9]?)\.(?:25[0-5]|2[0-4][0-9]|[01]?[0-9][0-9]?)\.(?:25[0-5]|2[0-4][0-9]|[01]?[0-9][0-9]?)\.(?:25[0-5]|2[0-4][0-9]|[01]?[0-9][0-9]?)(:\d+)?$";
@@ -604,6 +604,8 @@ pub mod register_pk_response {
         NOT_SUPPORT = 6,
         // @@protoc_insertion_point(enum_value:nimbus.RegisterPkResponse.Result.SERVER_ERROR)
         SERVER_ERROR = 7,
+        // @@protoc_insertion_point(enum_value:nimbus.RegisterPkResponse.Result.DISABLED)
+        DISABLED = 8,
     }
 
     impl ::protobuf::Enum for Result {
@@ -622,6 +624,7 @@ pub mod register_pk_response {
                 5 => ::std::option::Option::Some(Result::INVALID_ID_FORMAT),
                 6 => ::std::option::Option::Some(Result::NOT_SUPPORT),
                 7 => ::std::option::Option::Some(Result::SERVER_ERROR),
+                8 => ::std::option::Option::Some(Result::DISABLED),
                 _ => ::std::option::Option::None
             }
         }
@@ -635,6 +638,7 @@ pub mod register_pk_response {
                 "INVALID_ID_FORMAT" => ::std::option::Option::Some(Result::INVALID_ID_FORMAT),
                 "NOT_SUPPORT" => ::std::option::Option::Some(Result::NOT_SUPPORT),
                 "SERVER_ERROR" => ::std::option::Option::Some(Result::SERVER_ERROR),
+                "DISABLED" => ::std::option::Option::Some(Result::DISABLED),
                 _ => ::std::option::Option::None
             }
         }
@@ -647,6 +651,7 @@ pub mod register_pk_response {
             Result::INVALID_ID_FORMAT,
             Result::NOT_SUPPORT,
             Result::SERVER_ERROR,
+            Result::DISABLED,
         ];
     }
 
@@ -665,6 +670,7 @@ pub mod register_pk_response {
                 Result::INVALID_ID_FORMAT => 4,
                 Result::NOT_SUPPORT => 5,
                 Result::SERVER_ERROR => 6,
+                Result::DISABLED => 7,
             };
             Self::enum_descriptor().value_by_index(index)
         }
@@ -683,6 +689,164 @@ pub mod register_pk_response {
     }
 }
 
+// @@protoc_insertion_point(message:nimbus.PunchHoleRequest)
+#[derive(PartialEq,Clone,Default,Debug)]
+pub struct PunchHoleRequest {
+    // message fields
+    // @@protoc_insertion_point(field:nimbus.PunchHoleRequest.id)
+    pub id: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.PunchHoleRequest.nat_type)
+    pub nat_type: ::protobuf::EnumOrUnknown<NatType>,
+    // @@protoc_insertion_point(field:nimbus.PunchHoleRequest.licence_key)
+    pub licence_key: ::std::string::String,
+    // special fields
+    // @@protoc_insertion_point(special_field:nimbus.PunchHoleRequest.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a PunchHoleRequest {
+    fn default() -> &'a PunchHoleRequest {
+        <PunchHoleRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl PunchHoleRequest {
+    pub fn new() -> PunchHoleRequest {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(3);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "id",
+            |m: &PunchHoleRequest| { &m.id },
+            |m: &mut PunchHoleRequest| { &mut m.id },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "nat_type",
+            |m: &PunchHoleRequest| { &m.nat_type },
+            |m: &mut PunchHoleRequest| { &mut m.nat_type },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "licence_key",
+            |m: &PunchHoleRequest| { &m.licence_key },
+            |m: &mut PunchHoleRequest| { &mut m.licence_key },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<PunchHoleRequest>(
+            "PunchHoleRequest",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for PunchHoleRequest {
+    const NAME: &'static str = "PunchHoleRequest";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                10 => {
+                    self.id = is.read_string()?;
+                },
+                16 => {
+                    self.nat_type = is.read_enum_or_unknown()?;
+                },
+                26 => {
+                    self.licence_key = is.read_string()?;
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.id);
+        }
+        if self.nat_type != ::protobuf::EnumOrUnknown::new(NatType::UNKNOWN_NAT) {
+            my_size += ::protobuf::rt::int32_size(2, self.nat_type.value());
+        }
+        if !self.licence_key.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.licence_key);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if !self.id.is_empty() {
+            os.write_string(1, &self.id)?;
+        }
+        if self.nat_type != ::protobuf::EnumOrUnknown::new(NatType::UNKNOWN_NAT) {
+            os.write_enum(2, ::protobuf::EnumOrUnknown::value(&self.nat_type))?;
+        }
+        if !self.licence_key.is_empty() {
+            os.write_string(3, &self.licence_key)?;
+        }
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> PunchHoleRequest {
+        PunchHoleRequest::new()
+    }
+
+    fn clear(&mut self) {
+        self.id.clear();
+        self.nat_type = ::protobuf::EnumOrUnknown::new(NatType::UNKNOWN_NAT);
+        self.licence_key.clear();
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static PunchHoleRequest {
+        static instance: PunchHoleRequest = PunchHoleRequest {
+            id: ::std::string::String::new(),
+            nat_type: ::protobuf::EnumOrUnknown::from_i32(0),
+            licence_key: ::std::string::String::new(),
+            special_fields: ::protobuf::SpecialFields::new(),
+        };
+        &instance
+    }
+}
+
+impl ::protobuf::MessageFull for PunchHoleRequest {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("PunchHoleRequest").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for PunchHoleRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for PunchHoleRequest {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
 // @@protoc_insertion_point(message:nimbus.PunchHole)
 #[derive(PartialEq,Clone,Default,Debug)]
 pub struct PunchHole {
@@ -841,53 +1005,67 @@ impl ::protobuf::reflect::ProtobufValue for PunchHole {
     type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
 }
 
-// @@protoc_insertion_point(message:nimbus.ConfigUpdate)
+// @@protoc_insertion_point(message:nimbus.PunchHoleSent)
 #[derive(PartialEq,Clone,Default,Debug)]
-pub struct ConfigUpdate {
+pub struct PunchHoleSent {
     // message fields
-    // @@protoc_insertion_point(field:nimbus.ConfigUpdate.serial)
-    pub serial: i32,
-    // @@protoc_insertion_point(field:nimbus.ConfigUpdate.rendezvous_servers)
-    pub rendezvous_servers: ::std::vec::Vec<::std::string::String>,
+    // @@protoc_insertion_point(field:nimbus.PunchHoleSent.socket_addr)
+    pub socket_addr: ::bytes::Bytes,
+    // @@protoc_insertion_point(field:nimbus.PunchHoleSent.id)
+    pub id: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.PunchHoleSent.relay_server)
+    pub relay_server: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.PunchHoleSent.nat_type)
+    pub nat_type: ::protobuf::EnumOrUnknown<NatType>,
     // special fields
-    // @@protoc_insertion_point(special_field:nimbus.ConfigUpdate.special_fields)
+    // @@protoc_insertion_point(special_field:nimbus.PunchHoleSent.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
 }
 
-impl<'a> ::std::default::Default for &'a ConfigUpdate {
-    fn default() -> &'a ConfigUpdate {
-        <ConfigUpdate as ::protobuf::Message>::default_instance()
+impl<'a> ::std::default::Default for &'a PunchHoleSent {
+    fn default() -> &'a PunchHoleSent {
+        <PunchHoleSent as ::protobuf::Message>::default_instance()
     }
 }
 
-impl ConfigUpdate {
-    pub fn new() -> ConfigUpdate {
+impl PunchHoleSent {
+    pub fn new() -> PunchHoleSent {
         ::std::default::Default::default()
     }
 
     fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
-        let mut fields = ::std::vec::Vec::with_capacity(2);
+        let mut fields = ::std::vec::Vec::with_capacity(4);
         let mut oneofs = ::std::vec::Vec::with_capacity(0);
         fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
-            "serial",
-            |m: &ConfigUpdate| { &m.serial },
-            |m: &mut ConfigUpdate| { &mut m.serial },
+            "socket_addr",
+            |m: &PunchHoleSent| { &m.socket_addr },
+            |m: &mut PunchHoleSent| { &mut m.socket_addr },
         ));
-        fields.push(::protobuf::reflect::rt::v2::make_vec_simpler_accessor::<_, _>(
-            "rendezvous_servers",
-            |m: &ConfigUpdate| { &m.rendezvous_servers },
-            |m: &mut ConfigUpdate| { &mut m.rendezvous_servers },
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "id",
+            |m: &PunchHoleSent| { &m.id },
+            |m: &mut PunchHoleSent| { &mut m.id },
         ));
-        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<ConfigUpdate>(
-            "ConfigUpdate",
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "relay_server",
+            |m: &PunchHoleSent| { &m.relay_server },
+            |m: &mut PunchHoleSent| { &mut m.relay_server },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "nat_type",
+            |m: &PunchHoleSent| { &m.nat_type },
+            |m: &mut PunchHoleSent| { &mut m.nat_type },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<PunchHoleSent>(
+            "PunchHoleSent",
             fields,
             oneofs,
         )
     }
 }
 
-impl ::protobuf::Message for ConfigUpdate {
-    const NAME: &'static str = "ConfigUpdate";
+impl ::protobuf::Message for PunchHoleSent {
+    const NAME: &'static str = "PunchHoleSent";
 
     fn is_initialized(&self) -> bool {
         true
@@ -896,11 +1074,17 @@ impl ::protobuf::Message for ConfigUpdate {
     fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
         while let Some(tag) = is.read_raw_tag_or_eof()? {
             match tag {
-                8 => {
-                    self.serial = is.read_int32()?;
+                10 => {
+                    self.socket_addr = is.read_tokio_bytes()?;
                 },
                 18 => {
-                    self.rendezvous_servers.push(is.read_string()?);
+                    self.id = is.read_string()?;
+                },
+                26 => {
+                    self.relay_server = is.read_string()?;
+                },
+                32 => {
+                    self.nat_type = is.read_enum_or_unknown()?;
                 },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
@@ -914,24 +1098,36 @@ impl ::protobuf::Message for ConfigUpdate {
     #[allow(unused_variables)]
     fn compute_size(&self) -> u64 {
         let mut my_size = 0;
-        if self.serial != 0 {
-            my_size += ::protobuf::rt::int32_size(1, self.serial);
+        if !self.socket_addr.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.socket_addr);
+        }
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.id);
+        }
+        if !self.relay_server.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.relay_server);
+        }
+        if self.nat_type != ::protobuf::EnumOrUnknown::new(NatType::UNKNOWN_NAT) {
+            my_size += ::protobuf::rt::int32_size(4, self.nat_type.value());
         }
-        for value in &self.rendezvous_servers {
-            my_size += ::protobuf::rt::string_size(2, &value);
-        };
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
         my_size
     }
 
     fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
-        if self.serial != 0 {
-            os.write_int32(1, self.serial)?;
+        if !self.socket_addr.is_empty() {
+            os.write_bytes(1, &self.socket_addr)?;
+        }
+        if !self.id.is_empty() {
+            os.write_string(2, &self.id)?;
+        }
+        if !self.relay_server.is_empty() {
+            os.write_string(3, &self.relay_server)?;
+        }
+        if self.nat_type != ::protobuf::EnumOrUnknown::new(NatType::UNKNOWN_NAT) {
+            os.write_enum(4, ::protobuf::EnumOrUnknown::value(&self.nat_type))?;
         }
-        for v in &self.rendezvous_servers {
-            os.write_string(2, &v)?;
-        };
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -944,83 +1140,122 @@ impl ::protobuf::Message for ConfigUpdate {
         &mut self.special_fields
     }
 
-    fn new() -> ConfigUpdate {
-        ConfigUpdate::new()
+    fn new() -> PunchHoleSent {
+        PunchHoleSent::new()
     }
 
     fn clear(&mut self) {
-        self.serial = 0;
-        self.rendezvous_servers.clear();
+        self.socket_addr.clear();
+        self.id.clear();
+        self.relay_server.clear();
+        self.nat_type = ::protobuf::EnumOrUnknown::new(NatType::UNKNOWN_NAT);
         self.special_fields.clear();
     }
 
-    fn default_instance() -> &'static ConfigUpdate {
-        static instance: ConfigUpdate = ConfigUpdate {
-            serial: 0,
-            rendezvous_servers: ::std::vec::Vec::new(),
+    fn default_instance() -> &'static PunchHoleSent {
+        static instance: PunchHoleSent = PunchHoleSent {
+            socket_addr: ::bytes::Bytes::new(),
+            id: ::std::string::String::new(),
+            relay_server: ::std::string::String::new(),
+            nat_type: ::protobuf::EnumOrUnknown::from_i32(0),
             special_fields: ::protobuf::SpecialFields::new(),
         };
         &instance
     }
 }
 
-impl ::protobuf::MessageFull for ConfigUpdate {
+impl ::protobuf::MessageFull for PunchHoleSent {
     fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
         static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
-        descriptor.get(|| file_descriptor().message_by_package_relative_name("ConfigUpdate").unwrap()).clone()
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("PunchHoleSent").unwrap()).clone()
     }
 }
 
-impl ::std::fmt::Display for ConfigUpdate {
+impl ::std::fmt::Display for PunchHoleSent {
     fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for ConfigUpdate {
+impl ::protobuf::reflect::ProtobufValue for PunchHoleSent {
     type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
 }
 
-// @@protoc_insertion_point(message:nimbus.TestNatRequest)
+// @@protoc_insertion_point(message:nimbus.PunchHoleResponse)
 #[derive(PartialEq,Clone,Default,Debug)]
-pub struct TestNatRequest {
+pub struct PunchHoleResponse {
     // message fields
-    // @@protoc_insertion_point(field:nimbus.TestNatRequest.serial)
-    pub serial: i32,
+    // @@protoc_insertion_point(field:nimbus.PunchHoleResponse.socket_addr)
+    pub socket_addr: ::bytes::Bytes,
+    // @@protoc_insertion_point(field:nimbus.PunchHoleResponse.pk)
+    pub pk: ::bytes::Bytes,
+    // @@protoc_insertion_point(field:nimbus.PunchHoleResponse.failure)
+    pub failure: ::protobuf::EnumOrUnknown<punch_hole_response::Failure>,
+    // @@protoc_insertion_point(field:nimbus.PunchHoleResponse.relay_server)
+    pub relay_server: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.PunchHoleResponse.nat_type)
+    pub nat_type: ::protobuf::EnumOrUnknown<NatType>,
+    // @@protoc_insertion_point(field:nimbus.PunchHoleResponse.is_local)
+    pub is_local: bool,
     // special fields
-    // @@protoc_insertion_point(special_field:nimbus.TestNatRequest.special_fields)
+    // @@protoc_insertion_point(special_field:nimbus.PunchHoleResponse.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
 }
 
-impl<'a> ::std::default::Default for &'a TestNatRequest {
-    fn default() -> &'a TestNatRequest {
-        <TestNatRequest as ::protobuf::Message>::default_instance()
+impl<'a> ::std::default::Default for &'a PunchHoleResponse {
+    fn default() -> &'a PunchHoleResponse {
+        <PunchHoleResponse as ::protobuf::Message>::default_instance()
     }
 }
 
-impl TestNatRequest {
-    pub fn new() -> TestNatRequest {
+impl PunchHoleResponse {
+    pub fn new() -> PunchHoleResponse {
         ::std::default::Default::default()
     }
 
     fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
-        let mut fields = ::std::vec::Vec::with_capacity(1);
+        let mut fields = ::std::vec::Vec::with_capacity(6);
         let mut oneofs = ::std::vec::Vec::with_capacity(0);
         fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
-            "serial",
-            |m: &TestNatRequest| { &m.serial },
-            |m: &mut TestNatRequest| { &mut m.serial },
+            "socket_addr",
+            |m: &PunchHoleResponse| { &m.socket_addr },
+            |m: &mut PunchHoleResponse| { &mut m.socket_addr },
         ));
-        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<TestNatRequest>(
-            "TestNatRequest",
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "pk",
+            |m: &PunchHoleResponse| { &m.pk },
+            |m: &mut PunchHoleResponse| { &mut m.pk },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "failure",
+            |m: &PunchHoleResponse| { &m.failure },
+            |m: &mut PunchHoleResponse| { &mut m.failure },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "relay_server",
+            |m: &PunchHoleResponse| { &m.relay_server },
+            |m: &mut PunchHoleResponse| { &mut m.relay_server },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "nat_type",
+            |m: &PunchHoleResponse| { &m.nat_type },
+            |m: &mut PunchHoleResponse| { &mut m.nat_type },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "is_local",
+            |m: &PunchHoleResponse| { &m.is_local },
+            |m: &mut PunchHoleResponse| { &mut m.is_local },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<PunchHoleResponse>(
+            "PunchHoleResponse",
             fields,
             oneofs,
         )
     }
 }
 
-impl ::protobuf::Message for TestNatRequest {
-    const NAME: &'static str = "TestNatRequest";
+impl ::protobuf::Message for PunchHoleResponse {
+    const NAME: &'static str = "PunchHoleResponse";
 
     fn is_initialized(&self) -> bool {
         true
@@ -1029,11 +1264,1392 @@ impl ::protobuf::Message for TestNatRequest {
     fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
         while let Some(tag) = is.read_raw_tag_or_eof()? {
             match tag {
-                8 => {
-                    self.serial = is.read_int32()?;
-                },
-                tag => {
-                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                10 => {
+                    self.socket_addr = is.read_tokio_bytes()?;
+                },
+                18 => {
+                    self.pk = is.read_tokio_bytes()?;
+                },
+                24 => {
+                    self.failure = is.read_enum_or_unknown()?;
+                },
+                34 => {
+                    self.relay_server = is.read_string()?;
+                },
+                40 => {
+                    self.nat_type = is.read_enum_or_unknown()?;
+                },
+                48 => {
+                    self.is_local = is.read_bool()?;
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if !self.socket_addr.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.socket_addr);
+        }
+        if !self.pk.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(2, &self.pk);
+        }
+        if self.failure != ::protobuf::EnumOrUnknown::new(punch_hole_response::Failure::NO_FAILURE) {
+            my_size += ::protobuf::rt::int32_size(3, self.failure.value());
+        }
+        if !self.relay_server.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.relay_server);
+        }
+        if self.nat_type != ::protobuf::EnumOrUnknown::new(NatType::UNKNOWN_NAT) {
+            my_size += ::protobuf::rt::int32_size(5, self.nat_type.value());
+        }
+        if self.is_local != false {
+            my_size += 1 + 1;
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if !self.socket_addr.is_empty() {
+            os.write_bytes(1, &self.socket_addr)?;
+        }
+        if !self.pk.is_empty() {
+            os.write_bytes(2, &self.pk)?;
+        }
+        if self.failure != ::protobuf::EnumOrUnknown::new(punch_hole_response::Failure::NO_FAILURE) {
+            os.write_enum(3, ::protobuf::EnumOrUnknown::value(&self.failure))?;
+        }
+        if !self.relay_server.is_empty() {
+            os.write_string(4, &self.relay_server)?;
+        }
+        if self.nat_type != ::protobuf::EnumOrUnknown::new(NatType::UNKNOWN_NAT) {
+            os.write_enum(5, ::protobuf::EnumOrUnknown::value(&self.nat_type))?;
+        }
+        if self.is_local != false {
+            os.write_bool(6, self.is_local)?;
+        }
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> PunchHoleResponse {
+        PunchHoleResponse::new()
+    }
+
+    fn clear(&mut self) {
+        self.socket_addr.clear();
+        self.pk.clear();
+        self.failure = ::protobuf::EnumOrUnknown::new(punch_hole_response::Failure::NO_FAILURE);
+        self.relay_server.clear();
+        self.nat_type = ::protobuf::EnumOrUnknown::new(NatType::UNKNOWN_NAT);
+        self.is_local = false;
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static PunchHoleResponse {
+        static instance: PunchHoleResponse = PunchHoleResponse {
+            socket_addr: ::bytes::Bytes::new(),
+            pk: ::bytes::Bytes::new(),
+            failure: ::protobuf::EnumOrUnknown::from_i32(0),
+            relay_server: ::std::string::String::new(),
+            nat_type: ::protobuf::EnumOrUnknown::from_i32(0),
+            is_local: false,
+            special_fields: ::protobuf::SpecialFields::new(),
+        };
+        &instance
+    }
+}
+
+impl ::protobuf::MessageFull for PunchHoleResponse {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("PunchHoleResponse").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for PunchHoleResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for PunchHoleResponse {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
+/// Nested message and enums of message `PunchHoleResponse`
+pub mod punch_hole_response {
+    #[derive(Clone,Copy,PartialEq,Eq,Debug,Hash)]
+    // @@protoc_insertion_point(enum:nimbus.PunchHoleResponse.Failure)
+    pub enum Failure {
+        // @@protoc_insertion_point(enum_value:nimbus.PunchHoleResponse.Failure.NO_FAILURE)
+        NO_FAILURE = 0,
+        // @@protoc_insertion_point(enum_value:nimbus.PunchHoleResponse.Failure.ID_NOT_EXIST)
+        ID_NOT_EXIST = 1,
+        // @@protoc_insertion_point(enum_value:nimbus.PunchHoleResponse.Failure.OFFLINE)
+        OFFLINE = 2,
+        // @@protoc_insertion_point(enum_value:nimbus.PunchHoleResponse.Failure.LICENSE_MISMATCH)
+        LICENSE_MISMATCH = 3,
+        // @@protoc_insertion_point(enum_value:nimbus.PunchHoleResponse.Failure.DISABLED)
+        DISABLED = 4,
+    }
+
+    impl ::protobuf::Enum for Failure {
+        const NAME: &'static str = "Failure";
+
+        fn value(&self) -> i32 {
+            *self as i32
+        }
+
+        fn from_i32(value: i32) -> ::std::option::Option<Failure> {
+            match value {
+                0 => ::std::option::Option::Some(Failure::NO_FAILURE),
+                1 => ::std::option::Option::Some(Failure::ID_NOT_EXIST),
+                2 => ::std::option::Option::Some(Failure::OFFLINE),
+                3 => ::std::option::Option::Some(Failure::LICENSE_MISMATCH),
+                4 => ::std::option::Option::Some(Failure::DISABLED),
+                _ => ::std::option::Option::None
+            }
+        }
+
+        fn from_str(str: &str) -> ::std::option::Option<Failure> {
+            match str {
+                "NO_FAILURE" => ::std::option::Option::Some(Failure::NO_FAILURE),
+                "ID_NOT_EXIST" => ::std::option::Option::Some(Failure::ID_NOT_EXIST),
+                "OFFLINE" => ::std::option::Option::Some(Failure::OFFLINE),
+                "LICENSE_MISMATCH" => ::std::option::Option::Some(Failure::LICENSE_MISMATCH),
+                "DISABLED" => ::std::option::Option::Some(Failure::DISABLED),
+                _ => ::std::option::Option::None
+            }
+        }
+
+        const VALUES: &'static [Failure] = &[
+            Failure::NO_FAILURE,
+            Failure::ID_NOT_EXIST,
+            Failure::OFFLINE,
+            Failure::LICENSE_MISMATCH,
+            Failure::DISABLED,
+        ];
+    }
+
+    impl ::protobuf::EnumFull for Failure {
+        fn enum_descriptor() -> ::protobuf::reflect::EnumDescriptor {
+            static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::EnumDescriptor> = ::protobuf::rt::Lazy::new();
+            descriptor.get(|| super::file_descriptor().enum_by_package_relative_name("PunchHoleResponse.Failure").unwrap()).clone()
+        }
+
+        fn descriptor(&self) -> ::protobuf::reflect::EnumValueDescriptor {
+            let index = *self as usize;
+            Self::enum_descriptor().value_by_index(index)
+        }
+    }
+
+    impl ::std::default::Default for Failure {
+        fn default() -> Self {
+            Failure::NO_FAILURE
+        }
+    }
+
+    impl Failure {
+        pub(in super) fn generated_enum_descriptor_data() -> ::protobuf::reflect::GeneratedEnumDescriptorData {
+            ::protobuf::reflect::GeneratedEnumDescriptorData::new::<Failure>("PunchHoleResponse.Failure")
+        }
+    }
+}
+
+// @@protoc_insertion_point(message:nimbus.FetchLocalAddr)
+#[derive(PartialEq,Clone,Default,Debug)]
+pub struct FetchLocalAddr {
+    // message fields
+    // @@protoc_insertion_point(field:nimbus.FetchLocalAddr.socket_addr)
+    pub socket_addr: ::bytes::Bytes,
+    // @@protoc_insertion_point(field:nimbus.FetchLocalAddr.relay_server)
+    pub relay_server: ::std::string::String,
+    // special fields
+    // @@protoc_insertion_point(special_field:nimbus.FetchLocalAddr.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a FetchLocalAddr {
+    fn default() -> &'a FetchLocalAddr {
+        <FetchLocalAddr as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl FetchLocalAddr {
+    pub fn new() -> FetchLocalAddr {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(2);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "socket_addr",
+            |m: &FetchLocalAddr| { &m.socket_addr },
+            |m: &mut FetchLocalAddr| { &mut m.socket_addr },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "relay_server",
+            |m: &FetchLocalAddr| { &m.relay_server },
+            |m: &mut FetchLocalAddr| { &mut m.relay_server },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<FetchLocalAddr>(
+            "FetchLocalAddr",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for FetchLocalAddr {
+    const NAME: &'static str = "FetchLocalAddr";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                10 => {
+                    self.socket_addr = is.read_tokio_bytes()?;
+                },
+                18 => {
+                    self.relay_server = is.read_string()?;
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if !self.socket_addr.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.socket_addr);
+        }
+        if !self.relay_server.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.relay_server);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if !self.socket_addr.is_empty() {
+            os.write_bytes(1, &self.socket_addr)?;
+        }
+        if !self.relay_server.is_empty() {
+            os.write_string(2, &self.relay_server)?;
+        }
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> FetchLocalAddr {
+        FetchLocalAddr::new()
+    }
+
+    fn clear(&mut self) {
+        self.socket_addr.clear();
+        self.relay_server.clear();
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static FetchLocalAddr {
+        static instance: FetchLocalAddr = FetchLocalAddr {
+            socket_addr: ::bytes::Bytes::new(),
+            relay_server: ::std::string::String::new(),
+            special_fields: ::protobuf::SpecialFields::new(),
+        };
+        &instance
+    }
+}
+
+impl ::protobuf::MessageFull for FetchLocalAddr {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("FetchLocalAddr").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for FetchLocalAddr {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for FetchLocalAddr {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
+// @@protoc_insertion_point(message:nimbus.LocalAddr)
+#[derive(PartialEq,Clone,Default,Debug)]
+pub struct LocalAddr {
+    // message fields
+    // @@protoc_insertion_point(field:nimbus.LocalAddr.socket_addr)
+    pub socket_addr: ::bytes::Bytes,
+    // @@protoc_insertion_point(field:nimbus.LocalAddr.local_addr)
+    pub local_addr: ::bytes::Bytes,
+    // @@protoc_insertion_point(field:nimbus.LocalAddr.relay_server)
+    pub relay_server: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.LocalAddr.id)
+    pub id: ::std::string::String,
+    // special fields
+    // @@protoc_insertion_point(special_field:nimbus.LocalAddr.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a LocalAddr {
+    fn default() -> &'a LocalAddr {
+        <LocalAddr as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl LocalAddr {
+    pub fn new() -> LocalAddr {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(4);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "socket_addr",
+            |m: &LocalAddr| { &m.socket_addr },
+            |m: &mut LocalAddr| { &mut m.socket_addr },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "local_addr",
+            |m: &LocalAddr| { &m.local_addr },
+            |m: &mut LocalAddr| { &mut m.local_addr },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "relay_server",
+            |m: &LocalAddr| { &m.relay_server },
+            |m: &mut LocalAddr| { &mut m.relay_server },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "id",
+            |m: &LocalAddr| { &m.id },
+            |m: &mut LocalAddr| { &mut m.id },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<LocalAddr>(
+            "LocalAddr",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for LocalAddr {
+    const NAME: &'static str = "LocalAddr";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                10 => {
+                    self.socket_addr = is.read_tokio_bytes()?;
+                },
+                18 => {
+                    self.local_addr = is.read_tokio_bytes()?;
+                },
+                26 => {
+                    self.relay_server = is.read_string()?;
+                },
+                34 => {
+                    self.id = is.read_string()?;
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if !self.socket_addr.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.socket_addr);
+        }
+        if !self.local_addr.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(2, &self.local_addr);
+        }
+        if !self.relay_server.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.relay_server);
+        }
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.id);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if !self.socket_addr.is_empty() {
+            os.write_bytes(1, &self.socket_addr)?;
+        }
+        if !self.local_addr.is_empty() {
+            os.write_bytes(2, &self.local_addr)?;
+        }
+        if !self.relay_server.is_empty() {
+            os.write_string(3, &self.relay_server)?;
+        }
+        if !self.id.is_empty() {
+            os.write_string(4, &self.id)?;
+        }
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> LocalAddr {
+        LocalAddr::new()
+    }
+
+    fn clear(&mut self) {
+        self.socket_addr.clear();
+        self.local_addr.clear();
+        self.relay_server.clear();
+        self.id.clear();
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static LocalAddr {
+        static instance: LocalAddr = LocalAddr {
+            socket_addr: ::bytes::Bytes::new(),
+            local_addr: ::bytes::Bytes::new(),
+            relay_server: ::std::string::String::new(),
+            id: ::std::string::String::new(),
+            special_fields: ::protobuf::SpecialFields::new(),
+        };
+        &instance
+    }
+}
+
+impl ::protobuf::MessageFull for LocalAddr {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("LocalAddr").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for LocalAddr {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for LocalAddr {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
+// @@protoc_insertion_point(message:nimbus.RequestRelay)
+#[derive(PartialEq,Clone,Default,Debug)]
+pub struct RequestRelay {
+    // message fields
+    // @@protoc_insertion_point(field:nimbus.RequestRelay.id)
+    pub id: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.RequestRelay.uuid)
+    pub uuid: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.RequestRelay.socket_addr)
+    pub socket_addr: ::bytes::Bytes,
+    // @@protoc_insertion_point(field:nimbus.RequestRelay.relay_server)
+    pub relay_server: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.RequestRelay.licence_key)
+    pub licence_key: ::std::string::String,
+    // special fields
+    // @@protoc_insertion_point(special_field:nimbus.RequestRelay.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a RequestRelay {
+    fn default() -> &'a RequestRelay {
+        <RequestRelay as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl RequestRelay {
+    pub fn new() -> RequestRelay {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(5);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "id",
+            |m: &RequestRelay| { &m.id },
+            |m: &mut RequestRelay| { &mut m.id },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "uuid",
+            |m: &RequestRelay| { &m.uuid },
+            |m: &mut RequestRelay| { &mut m.uuid },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "socket_addr",
+            |m: &RequestRelay| { &m.socket_addr },
+            |m: &mut RequestRelay| { &mut m.socket_addr },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "relay_server",
+            |m: &RequestRelay| { &m.relay_server },
+            |m: &mut RequestRelay| { &mut m.relay_server },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "licence_key",
+            |m: &RequestRelay| { &m.licence_key },
+            |m: &mut RequestRelay| { &mut m.licence_key },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<RequestRelay>(
+            "RequestRelay",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for RequestRelay {
+    const NAME: &'static str = "RequestRelay";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                10 => {
+                    self.id = is.read_string()?;
+                },
+                18 => {
+                    self.uuid = is.read_string()?;
+                },
+                26 => {
+                    self.socket_addr = is.read_tokio_bytes()?;
+                },
+                34 => {
+                    self.relay_server = is.read_string()?;
+                },
+                50 => {
+                    self.licence_key = is.read_string()?;
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.id);
+        }
+        if !self.uuid.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.uuid);
+        }
+        if !self.socket_addr.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(3, &self.socket_addr);
+        }
+        if !self.relay_server.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.relay_server);
+        }
+        if !self.licence_key.is_empty() {
+            my_size += ::protobuf::rt::string_size(6, &self.licence_key);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if !self.id.is_empty() {
+            os.write_string(1, &self.id)?;
+        }
+        if !self.uuid.is_empty() {
+            os.write_string(2, &self.uuid)?;
+        }
+        if !self.socket_addr.is_empty() {
+            os.write_bytes(3, &self.socket_addr)?;
+        }
+        if !self.relay_server.is_empty() {
+            os.write_string(4, &self.relay_server)?;
+        }
+        if !self.licence_key.is_empty() {
+            os.write_string(6, &self.licence_key)?;
+        }
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> RequestRelay {
+        RequestRelay::new()
+    }
+
+    fn clear(&mut self) {
+        self.id.clear();
+        self.uuid.clear();
+        self.socket_addr.clear();
+        self.relay_server.clear();
+        self.licence_key.clear();
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static RequestRelay {
+        static instance: RequestRelay = RequestRelay {
+            id: ::std::string::String::new(),
+            uuid: ::std::string::String::new(),
+            socket_addr: ::bytes::Bytes::new(),
+            relay_server: ::std::string::String::new(),
+            licence_key: ::std::string::String::new(),
+            special_fields: ::protobuf::SpecialFields::new(),
+        };
+        &instance
+    }
+}
+
+impl ::protobuf::MessageFull for RequestRelay {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("RequestRelay").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for RequestRelay {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for RequestRelay {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
+// @@protoc_insertion_point(message:nimbus.RelayResponse)
+#[derive(PartialEq,Clone,Default,Debug)]
+pub struct RelayResponse {
+    // message fields
+    // @@protoc_insertion_point(field:nimbus.RelayResponse.socket_addr)
+    pub socket_addr: ::bytes::Bytes,
+    // @@protoc_insertion_point(field:nimbus.RelayResponse.uuid)
+    pub uuid: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.RelayResponse.relay_server)
+    pub relay_server: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.RelayResponse.id)
+    pub id: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.RelayResponse.pk)
+    pub pk: ::bytes::Bytes,
+    // @@protoc_insertion_point(field:nimbus.RelayResponse.refuse_reason)
+    pub refuse_reason: ::std::string::String,
+    // special fields
+    // @@protoc_insertion_point(special_field:nimbus.RelayResponse.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a RelayResponse {
+    fn default() -> &'a RelayResponse {
+        <RelayResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl RelayResponse {
+    pub fn new() -> RelayResponse {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(6);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "socket_addr",
+            |m: &RelayResponse| { &m.socket_addr },
+            |m: &mut RelayResponse| { &mut m.socket_addr },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "uuid",
+            |m: &RelayResponse| { &m.uuid },
+            |m: &mut RelayResponse| { &mut m.uuid },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "relay_server",
+            |m: &RelayResponse| { &m.relay_server },
+            |m: &mut RelayResponse| { &mut m.relay_server },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "id",
+            |m: &RelayResponse| { &m.id },
+            |m: &mut RelayResponse| { &mut m.id },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "pk",
+            |m: &RelayResponse| { &m.pk },
+            |m: &mut RelayResponse| { &mut m.pk },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "refuse_reason",
+            |m: &RelayResponse| { &m.refuse_reason },
+            |m: &mut RelayResponse| { &mut m.refuse_reason },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<RelayResponse>(
+            "RelayResponse",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for RelayResponse {
+    const NAME: &'static str = "RelayResponse";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                10 => {
+                    self.socket_addr = is.read_tokio_bytes()?;
+                },
+                18 => {
+                    self.uuid = is.read_string()?;
+                },
+                26 => {
+                    self.relay_server = is.read_string()?;
+                },
+                34 => {
+                    self.id = is.read_string()?;
+                },
+                42 => {
+                    self.pk = is.read_tokio_bytes()?;
+                },
+                50 => {
+                    self.refuse_reason = is.read_string()?;
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if !self.socket_addr.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.socket_addr);
+        }
+        if !self.uuid.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.uuid);
+        }
+        if !self.relay_server.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.relay_server);
+        }
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.id);
+        }
+        if !self.pk.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(5, &self.pk);
+        }
+        if !self.refuse_reason.is_empty() {
+            my_size += ::protobuf::rt::string_size(6, &self.refuse_reason);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if !self.socket_addr.is_empty() {
+            os.write_bytes(1, &self.socket_addr)?;
+        }
+        if !self.uuid.is_empty() {
+            os.write_string(2, &self.uuid)?;
+        }
+        if !self.relay_server.is_empty() {
+            os.write_string(3, &self.relay_server)?;
+        }
+        if !self.id.is_empty() {
+            os.write_string(4, &self.id)?;
+        }
+        if !self.pk.is_empty() {
+            os.write_bytes(5, &self.pk)?;
+        }
+        if !self.refuse_reason.is_empty() {
+            os.write_string(6, &self.refuse_reason)?;
+        }
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> RelayResponse {
+        RelayResponse::new()
+    }
+
+    fn clear(&mut self) {
+        self.socket_addr.clear();
+        self.uuid.clear();
+        self.relay_server.clear();
+        self.id.clear();
+        self.pk.clear();
+        self.refuse_reason.clear();
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static RelayResponse {
+        static instance: RelayResponse = RelayResponse {
+            socket_addr: ::bytes::Bytes::new(),
+            uuid: ::std::string::String::new(),
+            relay_server: ::std::string::String::new(),
+            id: ::std::string::String::new(),
+            pk: ::bytes::Bytes::new(),
+            refuse_reason: ::std::string::String::new(),
+            special_fields: ::protobuf::SpecialFields::new(),
+        };
+        &instance
+    }
+}
+
+impl ::protobuf::MessageFull for RelayResponse {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("RelayResponse").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for RelayResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for RelayResponse {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
+// @@protoc_insertion_point(message:nimbus.IdPk)
+#[derive(PartialEq,Clone,Default,Debug)]
+pub struct IdPk {
+    // message fields
+    // @@protoc_insertion_point(field:nimbus.IdPk.id)
+    pub id: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.IdPk.pk)
+    pub pk: ::bytes::Bytes,
+    // special fields
+    // @@protoc_insertion_point(special_field:nimbus.IdPk.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a IdPk {
+    fn default() -> &'a IdPk {
+        <IdPk as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl IdPk {
+    pub fn new() -> IdPk {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(2);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "id",
+            |m: &IdPk| { &m.id },
+            |m: &mut IdPk| { &mut m.id },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "pk",
+            |m: &IdPk| { &m.pk },
+            |m: &mut IdPk| { &mut m.pk },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<IdPk>(
+            "IdPk",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for IdPk {
+    const NAME: &'static str = "IdPk";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                10 => {
+                    self.id = is.read_string()?;
+                },
+                18 => {
+                    self.pk = is.read_tokio_bytes()?;
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.id);
+        }
+        if !self.pk.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(2, &self.pk);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if !self.id.is_empty() {
+            os.write_string(1, &self.id)?;
+        }
+        if !self.pk.is_empty() {
+            os.write_bytes(2, &self.pk)?;
+        }
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> IdPk {
+        IdPk::new()
+    }
+
+    fn clear(&mut self) {
+        self.id.clear();
+        self.pk.clear();
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static IdPk {
+        static instance: IdPk = IdPk {
+            id: ::std::string::String::new(),
+            pk: ::bytes::Bytes::new(),
+            special_fields: ::protobuf::SpecialFields::new(),
+        };
+        &instance
+    }
+}
+
+impl ::protobuf::MessageFull for IdPk {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("IdPk").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for IdPk {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for IdPk {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
+// @@protoc_insertion_point(message:nimbus.ConfigUpdate)
+#[derive(PartialEq,Clone,Default,Debug)]
+pub struct ConfigUpdate {
+    // message fields
+    // @@protoc_insertion_point(field:nimbus.ConfigUpdate.serial)
+    pub serial: i32,
+    // @@protoc_insertion_point(field:nimbus.ConfigUpdate.rendezvous_servers)
+    pub rendezvous_servers: ::std::vec::Vec<::std::string::String>,
+    // special fields
+    // @@protoc_insertion_point(special_field:nimbus.ConfigUpdate.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a ConfigUpdate {
+    fn default() -> &'a ConfigUpdate {
+        <ConfigUpdate as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl ConfigUpdate {
+    pub fn new() -> ConfigUpdate {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(2);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "serial",
+            |m: &ConfigUpdate| { &m.serial },
+            |m: &mut ConfigUpdate| { &mut m.serial },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_vec_simpler_accessor::<_, _>(
+            "rendezvous_servers",
+            |m: &ConfigUpdate| { &m.rendezvous_servers },
+            |m: &mut ConfigUpdate| { &mut m.rendezvous_servers },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<ConfigUpdate>(
+            "ConfigUpdate",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for ConfigUpdate {
+    const NAME: &'static str = "ConfigUpdate";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                8 => {
+                    self.serial = is.read_int32()?;
+                },
+                18 => {
+                    self.rendezvous_servers.push(is.read_string()?);
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if self.serial != 0 {
+            my_size += ::protobuf::rt::int32_size(1, self.serial);
+        }
+        for value in &self.rendezvous_servers {
+            my_size += ::protobuf::rt::string_size(2, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if self.serial != 0 {
+            os.write_int32(1, self.serial)?;
+        }
+        for v in &self.rendezvous_servers {
+            os.write_string(2, &v)?;
+        };
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> ConfigUpdate {
+        ConfigUpdate::new()
+    }
+
+    fn clear(&mut self) {
+        self.serial = 0;
+        self.rendezvous_servers.clear();
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static ConfigUpdate {
+        static instance: ConfigUpdate = ConfigUpdate {
+            serial: 0,
+            rendezvous_servers: ::std::vec::Vec::new(),
+            special_fields: ::protobuf::SpecialFields::new(),
+        };
+        &instance
+    }
+}
+
+impl ::protobuf::MessageFull for ConfigUpdate {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("ConfigUpdate").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for ConfigUpdate {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ConfigUpdate {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
+// @@protoc_insertion_point(message:nimbus.TestNatRequest)
+#[derive(PartialEq,Clone,Default,Debug)]
+pub struct TestNatRequest {
+    // message fields
+    // @@protoc_insertion_point(field:nimbus.TestNatRequest.serial)
+    pub serial: i32,
+    // special fields
+    // @@protoc_insertion_point(special_field:nimbus.TestNatRequest.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a TestNatRequest {
+    fn default() -> &'a TestNatRequest {
+        <TestNatRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl TestNatRequest {
+    pub fn new() -> TestNatRequest {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(1);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "serial",
+            |m: &TestNatRequest| { &m.serial },
+            |m: &mut TestNatRequest| { &mut m.serial },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<TestNatRequest>(
+            "TestNatRequest",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for TestNatRequest {
+    const NAME: &'static str = "TestNatRequest";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                8 => {
+                    self.serial = is.read_int32()?;
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if self.serial != 0 {
+            my_size += ::protobuf::rt::int32_size(1, self.serial);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if self.serial != 0 {
+            os.write_int32(1, self.serial)?;
+        }
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> TestNatRequest {
+        TestNatRequest::new()
+    }
+
+    fn clear(&mut self) {
+        self.serial = 0;
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static TestNatRequest {
+        static instance: TestNatRequest = TestNatRequest {
+            serial: 0,
+            special_fields: ::protobuf::SpecialFields::new(),
+        };
+        &instance
+    }
+}
+
+impl ::protobuf::MessageFull for TestNatRequest {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("TestNatRequest").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for TestNatRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for TestNatRequest {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
+// @@protoc_insertion_point(message:nimbus.TestNatResponse)
+#[derive(PartialEq,Clone,Default,Debug)]
+pub struct TestNatResponse {
+    // message fields
+    // @@protoc_insertion_point(field:nimbus.TestNatResponse.port)
+    pub port: i32,
+    // special fields
+    // @@protoc_insertion_point(special_field:nimbus.TestNatResponse.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a TestNatResponse {
+    fn default() -> &'a TestNatResponse {
+        <TestNatResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl TestNatResponse {
+    pub fn new() -> TestNatResponse {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(1);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "port",
+            |m: &TestNatResponse| { &m.port },
+            |m: &mut TestNatResponse| { &mut m.port },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<TestNatResponse>(
+            "TestNatResponse",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for TestNatResponse {
+    const NAME: &'static str = "TestNatResponse";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                8 => {
+                    self.port = is.read_int32()?;
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
                 },
             };
         }
@@ -1044,8 +2660,8 @@ impl ::protobuf::Message for TestNatRequest {
     #[allow(unused_variables)]
     fn compute_size(&self) -> u64 {
         let mut my_size = 0;
-        if self.serial != 0 {
-            my_size += ::protobuf::rt::int32_size(1, self.serial);
+        if self.port != 0 {
+            my_size += ::protobuf::rt::int32_size(1, self.port);
         }
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
@@ -1053,8 +2669,270 @@ impl ::protobuf::Message for TestNatRequest {
     }
 
     fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
-        if self.serial != 0 {
-            os.write_int32(1, self.serial)?;
+        if self.port != 0 {
+            os.write_int32(1, self.port)?;
+        }
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> TestNatResponse {
+        TestNatResponse::new()
+    }
+
+    fn clear(&mut self) {
+        self.port = 0;
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static TestNatResponse {
+        static instance: TestNatResponse = TestNatResponse {
+            port: 0,
+            special_fields: ::protobuf::SpecialFields::new(),
+        };
+        &instance
+    }
+}
+
+impl ::protobuf::MessageFull for TestNatResponse {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("TestNatResponse").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for TestNatResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for TestNatResponse {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
+// @@protoc_insertion_point(message:nimbus.OnlineRequest)
+#[derive(PartialEq,Clone,Default,Debug)]
+pub struct OnlineRequest {
+    // message fields
+    // @@protoc_insertion_point(field:nimbus.OnlineRequest.id)
+    pub id: ::std::string::String,
+    // @@protoc_insertion_point(field:nimbus.OnlineRequest.peers)
+    pub peers: ::std::vec::Vec<::std::string::String>,
+    // special fields
+    // @@protoc_insertion_point(special_field:nimbus.OnlineRequest.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a OnlineRequest {
+    fn default() -> &'a OnlineRequest {
+        <OnlineRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl OnlineRequest {
+    pub fn new() -> OnlineRequest {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(2);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "id",
+            |m: &OnlineRequest| { &m.id },
+            |m: &mut OnlineRequest| { &mut m.id },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_vec_simpler_accessor::<_, _>(
+            "peers",
+            |m: &OnlineRequest| { &m.peers },
+            |m: &mut OnlineRequest| { &mut m.peers },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<OnlineRequest>(
+            "OnlineRequest",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for OnlineRequest {
+    const NAME: &'static str = "OnlineRequest";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                10 => {
+                    self.id = is.read_string()?;
+                },
+                18 => {
+                    self.peers.push(is.read_string()?);
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.id);
+        }
+        for value in &self.peers {
+            my_size += ::protobuf::rt::string_size(2, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if !self.id.is_empty() {
+            os.write_string(1, &self.id)?;
+        }
+        for v in &self.peers {
+            os.write_string(2, &v)?;
+        };
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> OnlineRequest {
+        OnlineRequest::new()
+    }
+
+    fn clear(&mut self) {
+        self.id.clear();
+        self.peers.clear();
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static OnlineRequest {
+        static instance: OnlineRequest = OnlineRequest {
+            id: ::std::string::String::new(),
+            peers: ::std::vec::Vec::new(),
+            special_fields: ::protobuf::SpecialFields::new(),
+        };
+        &instance
+    }
+}
+
+impl ::protobuf::MessageFull for OnlineRequest {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("OnlineRequest").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for OnlineRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for OnlineRequest {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
+// @@protoc_insertion_point(message:nimbus.OnlineResponse)
+#[derive(PartialEq,Clone,Default,Debug)]
+pub struct OnlineResponse {
+    // message fields
+    // @@protoc_insertion_point(field:nimbus.OnlineResponse.states)
+    pub states: ::bytes::Bytes,
+    // special fields
+    // @@protoc_insertion_point(special_field:nimbus.OnlineResponse.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a OnlineResponse {
+    fn default() -> &'a OnlineResponse {
+        <OnlineResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl OnlineResponse {
+    pub fn new() -> OnlineResponse {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(1);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "states",
+            |m: &OnlineResponse| { &m.states },
+            |m: &mut OnlineResponse| { &mut m.states },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<OnlineResponse>(
+            "OnlineResponse",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for OnlineResponse {
+    const NAME: &'static str = "OnlineResponse";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                10 => {
+                    self.states = is.read_tokio_bytes()?;
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if !self.states.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.states);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if !self.states.is_empty() {
+            os.write_bytes(1, &self.states)?;
         }
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
@@ -1068,60 +2946,60 @@ impl ::protobuf::Message for TestNatRequest {
         &mut self.special_fields
     }
 
-    fn new() -> TestNatRequest {
-        TestNatRequest::new()
+    fn new() -> OnlineResponse {
+        OnlineResponse::new()
     }
 
     fn clear(&mut self) {
-        self.serial = 0;
+        self.states.clear();
         self.special_fields.clear();
     }
 
-    fn default_instance() -> &'static TestNatRequest {
-        static instance: TestNatRequest = TestNatRequest {
-            serial: 0,
+    fn default_instance() -> &'static OnlineResponse {
+        static instance: OnlineResponse = OnlineResponse {
+            states: ::bytes::Bytes::new(),
             special_fields: ::protobuf::SpecialFields::new(),
         };
         &instance
     }
 }
 
-impl ::protobuf::MessageFull for TestNatRequest {
+impl ::protobuf::MessageFull for OnlineResponse {
     fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
         static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
-        descriptor.get(|| file_descriptor().message_by_package_relative_name("TestNatRequest").unwrap()).clone()
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("OnlineResponse").unwrap()).clone()
     }
 }
 
-impl ::std::fmt::Display for TestNatRequest {
+impl ::std::fmt::Display for OnlineResponse {
     fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for TestNatRequest {
+impl ::protobuf::reflect::ProtobufValue for OnlineResponse {
     type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
 }
 
-// @@protoc_insertion_point(message:nimbus.TestNatResponse)
+// @@protoc_insertion_point(message:nimbus.HealthCheck)
 #[derive(PartialEq,Clone,Default,Debug)]
-pub struct TestNatResponse {
+pub struct HealthCheck {
     // message fields
-    // @@protoc_insertion_point(field:nimbus.TestNatResponse.port)
-    pub port: i32,
+    // @@protoc_insertion_point(field:nimbus.HealthCheck.token)
+    pub token: ::std::string::String,
     // special fields
-    // @@protoc_insertion_point(special_field:nimbus.TestNatResponse.special_fields)
+    // @@protoc_insertion_point(special_field:nimbus.HealthCheck.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
 }
 
-impl<'a> ::std::default::Default for &'a TestNatResponse {
-    fn default() -> &'a TestNatResponse {
-        <TestNatResponse as ::protobuf::Message>::default_instance()
+impl<'a> ::std::default::Default for &'a HealthCheck {
+    fn default() -> &'a HealthCheck {
+        <HealthCheck as ::protobuf::Message>::default_instance()
     }
 }
 
-impl TestNatResponse {
-    pub fn new() -> TestNatResponse {
+impl HealthCheck {
+    pub fn new() -> HealthCheck {
         ::std::default::Default::default()
     }
 
@@ -1129,20 +3007,20 @@ impl TestNatResponse {
         let mut fields = ::std::vec::Vec::with_capacity(1);
         let mut oneofs = ::std::vec::Vec::with_capacity(0);
         fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
-            "port",
-            |m: &TestNatResponse| { &m.port },
-            |m: &mut TestNatResponse| { &mut m.port },
+            "token",
+            |m: &HealthCheck| { &m.token },
+            |m: &mut HealthCheck| { &mut m.token },
         ));
-        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<TestNatResponse>(
-            "TestNatResponse",
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<HealthCheck>(
+            "HealthCheck",
             fields,
             oneofs,
         )
     }
 }
 
-impl ::protobuf::Message for TestNatResponse {
-    const NAME: &'static str = "TestNatResponse";
+impl ::protobuf::Message for HealthCheck {
+    const NAME: &'static str = "HealthCheck";
 
     fn is_initialized(&self) -> bool {
         true
@@ -1151,8 +3029,8 @@ impl ::protobuf::Message for TestNatResponse {
     fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
         while let Some(tag) = is.read_raw_tag_or_eof()? {
             match tag {
-                8 => {
-                    self.port = is.read_int32()?;
+                10 => {
+                    self.token = is.read_string()?;
                 },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
@@ -1166,8 +3044,8 @@ impl ::protobuf::Message for TestNatResponse {
     #[allow(unused_variables)]
     fn compute_size(&self) -> u64 {
         let mut my_size = 0;
-        if self.port != 0 {
-            my_size += ::protobuf::rt::int32_size(1, self.port);
+        if !self.token.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.token);
         }
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
@@ -1175,8 +3053,8 @@ impl ::protobuf::Message for TestNatResponse {
     }
 
     fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
-        if self.port != 0 {
-            os.write_int32(1, self.port)?;
+        if !self.token.is_empty() {
+            os.write_string(1, &self.token)?;
         }
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
@@ -1190,38 +3068,38 @@ impl ::protobuf::Message for TestNatResponse {
         &mut self.special_fields
     }
 
-    fn new() -> TestNatResponse {
-        TestNatResponse::new()
+    fn new() -> HealthCheck {
+        HealthCheck::new()
     }
 
     fn clear(&mut self) {
-        self.port = 0;
+        self.token.clear();
         self.special_fields.clear();
     }
 
-    fn default_instance() -> &'static TestNatResponse {
-        static instance: TestNatResponse = TestNatResponse {
-            port: 0,
+    fn default_instance() -> &'static HealthCheck {
+        static instance: HealthCheck = HealthCheck {
+            token: ::std::string::String::new(),
             special_fields: ::protobuf::SpecialFields::new(),
         };
         &instance
     }
 }
 
-impl ::protobuf::MessageFull for TestNatResponse {
+impl ::protobuf::MessageFull for HealthCheck {
     fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
         static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
-        descriptor.get(|| file_descriptor().message_by_package_relative_name("TestNatResponse").unwrap()).clone()
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("HealthCheck").unwrap()).clone()
     }
 }
 
-impl ::std::fmt::Display for TestNatResponse {
+impl ::std::fmt::Display for HealthCheck {
     fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for TestNatResponse {
+impl ::protobuf::reflect::ProtobufValue for HealthCheck {
     type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
 }
 
@@ -1287,60 +3165,354 @@ impl RendezvousMessage {
     pub fn take_register_peer(&mut self) -> RegisterPeer {
         if self.has_register_peer() {
             match self.union.take() {
-                ::std::option::Option::Some(rendezvous_message::Union::RegisterPeer(v)) => v,
+                ::std::option::Option::Some(rendezvous_message::Union::RegisterPeer(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            RegisterPeer::new()
+        }
+    }
+
+    // .nimbus.RegisterPeerResponse register_peer_response = 7;
+
+    pub fn register_peer_response(&self) -> &RegisterPeerResponse {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(ref v)) => v,
+            _ => <RegisterPeerResponse as ::protobuf::Message>::default_instance(),
+        }
+    }
+
+    pub fn clear_register_peer_response(&mut self) {
+        self.union = ::std::option::Option::None;
+    }
+
+    pub fn has_register_peer_response(&self) -> bool {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_register_peer_response(&mut self, v: RegisterPeerResponse) {
+        self.union = ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_register_peer_response(&mut self) -> &mut RegisterPeerResponse {
+        if let ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(_)) = self.union {
+        } else {
+            self.union = ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(RegisterPeerResponse::new()));
+        }
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_register_peer_response(&mut self) -> RegisterPeerResponse {
+        if self.has_register_peer_response() {
+            match self.union.take() {
+                ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            RegisterPeerResponse::new()
+        }
+    }
+
+    // .nimbus.PunchHoleRequest punch_hole_request = 8;
+
+    pub fn punch_hole_request(&self) -> &PunchHoleRequest {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::PunchHoleRequest(ref v)) => v,
+            _ => <PunchHoleRequest as ::protobuf::Message>::default_instance(),
+        }
+    }
+
+    pub fn clear_punch_hole_request(&mut self) {
+        self.union = ::std::option::Option::None;
+    }
+
+    pub fn has_punch_hole_request(&self) -> bool {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::PunchHoleRequest(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_punch_hole_request(&mut self, v: PunchHoleRequest) {
+        self.union = ::std::option::Option::Some(rendezvous_message::Union::PunchHoleRequest(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_punch_hole_request(&mut self) -> &mut PunchHoleRequest {
+        if let ::std::option::Option::Some(rendezvous_message::Union::PunchHoleRequest(_)) = self.union {
+        } else {
+            self.union = ::std::option::Option::Some(rendezvous_message::Union::PunchHoleRequest(PunchHoleRequest::new()));
+        }
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::PunchHoleRequest(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_punch_hole_request(&mut self) -> PunchHoleRequest {
+        if self.has_punch_hole_request() {
+            match self.union.take() {
+                ::std::option::Option::Some(rendezvous_message::Union::PunchHoleRequest(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            PunchHoleRequest::new()
+        }
+    }
+
+    // .nimbus.PunchHole punch_hole = 9;
+
+    pub fn punch_hole(&self) -> &PunchHole {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::PunchHole(ref v)) => v,
+            _ => <PunchHole as ::protobuf::Message>::default_instance(),
+        }
+    }
+
+    pub fn clear_punch_hole(&mut self) {
+        self.union = ::std::option::Option::None;
+    }
+
+    pub fn has_punch_hole(&self) -> bool {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::PunchHole(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_punch_hole(&mut self, v: PunchHole) {
+        self.union = ::std::option::Option::Some(rendezvous_message::Union::PunchHole(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_punch_hole(&mut self) -> &mut PunchHole {
+        if let ::std::option::Option::Some(rendezvous_message::Union::PunchHole(_)) = self.union {
+        } else {
+            self.union = ::std::option::Option::Some(rendezvous_message::Union::PunchHole(PunchHole::new()));
+        }
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::PunchHole(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_punch_hole(&mut self) -> PunchHole {
+        if self.has_punch_hole() {
+            match self.union.take() {
+                ::std::option::Option::Some(rendezvous_message::Union::PunchHole(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            PunchHole::new()
+        }
+    }
+
+    // .nimbus.PunchHoleSent punch_hole_sent = 10;
+
+    pub fn punch_hole_sent(&self) -> &PunchHoleSent {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::PunchHoleSent(ref v)) => v,
+            _ => <PunchHoleSent as ::protobuf::Message>::default_instance(),
+        }
+    }
+
+    pub fn clear_punch_hole_sent(&mut self) {
+        self.union = ::std::option::Option::None;
+    }
+
+    pub fn has_punch_hole_sent(&self) -> bool {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::PunchHoleSent(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_punch_hole_sent(&mut self, v: PunchHoleSent) {
+        self.union = ::std::option::Option::Some(rendezvous_message::Union::PunchHoleSent(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_punch_hole_sent(&mut self) -> &mut PunchHoleSent {
+        if let ::std::option::Option::Some(rendezvous_message::Union::PunchHoleSent(_)) = self.union {
+        } else {
+            self.union = ::std::option::Option::Some(rendezvous_message::Union::PunchHoleSent(PunchHoleSent::new()));
+        }
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::PunchHoleSent(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_punch_hole_sent(&mut self) -> PunchHoleSent {
+        if self.has_punch_hole_sent() {
+            match self.union.take() {
+                ::std::option::Option::Some(rendezvous_message::Union::PunchHoleSent(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            PunchHoleSent::new()
+        }
+    }
+
+    // .nimbus.PunchHoleResponse punch_hole_response = 11;
+
+    pub fn punch_hole_response(&self) -> &PunchHoleResponse {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::PunchHoleResponse(ref v)) => v,
+            _ => <PunchHoleResponse as ::protobuf::Message>::default_instance(),
+        }
+    }
+
+    pub fn clear_punch_hole_response(&mut self) {
+        self.union = ::std::option::Option::None;
+    }
+
+    pub fn has_punch_hole_response(&self) -> bool {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::PunchHoleResponse(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_punch_hole_response(&mut self, v: PunchHoleResponse) {
+        self.union = ::std::option::Option::Some(rendezvous_message::Union::PunchHoleResponse(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_punch_hole_response(&mut self) -> &mut PunchHoleResponse {
+        if let ::std::option::Option::Some(rendezvous_message::Union::PunchHoleResponse(_)) = self.union {
+        } else {
+            self.union = ::std::option::Option::Some(rendezvous_message::Union::PunchHoleResponse(PunchHoleResponse::new()));
+        }
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::PunchHoleResponse(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_punch_hole_response(&mut self) -> PunchHoleResponse {
+        if self.has_punch_hole_response() {
+            match self.union.take() {
+                ::std::option::Option::Some(rendezvous_message::Union::PunchHoleResponse(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            PunchHoleResponse::new()
+        }
+    }
+
+    // .nimbus.FetchLocalAddr fetch_local_addr = 12;
+
+    pub fn fetch_local_addr(&self) -> &FetchLocalAddr {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::FetchLocalAddr(ref v)) => v,
+            _ => <FetchLocalAddr as ::protobuf::Message>::default_instance(),
+        }
+    }
+
+    pub fn clear_fetch_local_addr(&mut self) {
+        self.union = ::std::option::Option::None;
+    }
+
+    pub fn has_fetch_local_addr(&self) -> bool {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::FetchLocalAddr(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_fetch_local_addr(&mut self, v: FetchLocalAddr) {
+        self.union = ::std::option::Option::Some(rendezvous_message::Union::FetchLocalAddr(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_fetch_local_addr(&mut self) -> &mut FetchLocalAddr {
+        if let ::std::option::Option::Some(rendezvous_message::Union::FetchLocalAddr(_)) = self.union {
+        } else {
+            self.union = ::std::option::Option::Some(rendezvous_message::Union::FetchLocalAddr(FetchLocalAddr::new()));
+        }
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::FetchLocalAddr(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_fetch_local_addr(&mut self) -> FetchLocalAddr {
+        if self.has_fetch_local_addr() {
+            match self.union.take() {
+                ::std::option::Option::Some(rendezvous_message::Union::FetchLocalAddr(v)) => v,
                 _ => panic!(),
             }
         } else {
-            RegisterPeer::new()
+            FetchLocalAddr::new()
         }
     }
 
-    // .nimbus.RegisterPeerResponse register_peer_response = 7;
+    // .nimbus.LocalAddr local_addr = 13;
 
-    pub fn register_peer_response(&self) -> &RegisterPeerResponse {
+    pub fn local_addr(&self) -> &LocalAddr {
         match self.union {
-            ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(ref v)) => v,
-            _ => <RegisterPeerResponse as ::protobuf::Message>::default_instance(),
+            ::std::option::Option::Some(rendezvous_message::Union::LocalAddr(ref v)) => v,
+            _ => <LocalAddr as ::protobuf::Message>::default_instance(),
         }
     }
 
-    pub fn clear_register_peer_response(&mut self) {
+    pub fn clear_local_addr(&mut self) {
         self.union = ::std::option::Option::None;
     }
 
-    pub fn has_register_peer_response(&self) -> bool {
+    pub fn has_local_addr(&self) -> bool {
         match self.union {
-            ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(..)) => true,
+            ::std::option::Option::Some(rendezvous_message::Union::LocalAddr(..)) => true,
             _ => false,
         }
     }
 
     // Param is passed by value, moved
-    pub fn set_register_peer_response(&mut self, v: RegisterPeerResponse) {
-        self.union = ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(v))
+    pub fn set_local_addr(&mut self, v: LocalAddr) {
+        self.union = ::std::option::Option::Some(rendezvous_message::Union::LocalAddr(v))
     }
 
     // Mutable pointer to the field.
-    pub fn mut_register_peer_response(&mut self) -> &mut RegisterPeerResponse {
-        if let ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(_)) = self.union {
+    pub fn mut_local_addr(&mut self) -> &mut LocalAddr {
+        if let ::std::option::Option::Some(rendezvous_message::Union::LocalAddr(_)) = self.union {
         } else {
-            self.union = ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(RegisterPeerResponse::new()));
+            self.union = ::std::option::Option::Some(rendezvous_message::Union::LocalAddr(LocalAddr::new()));
         }
         match self.union {
-            ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(ref mut v)) => v,
+            ::std::option::Option::Some(rendezvous_message::Union::LocalAddr(ref mut v)) => v,
             _ => panic!(),
         }
     }
 
     // Take field
-    pub fn take_register_peer_response(&mut self) -> RegisterPeerResponse {
-        if self.has_register_peer_response() {
+    pub fn take_local_addr(&mut self) -> LocalAddr {
+        if self.has_local_addr() {
             match self.union.take() {
-                ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(v)) => v,
+                ::std::option::Option::Some(rendezvous_message::Union::LocalAddr(v)) => v,
                 _ => panic!(),
             }
         } else {
-            RegisterPeerResponse::new()
+            LocalAddr::new()
         }
     }
 
@@ -1491,6 +3663,104 @@ impl RendezvousMessage {
         }
     }
 
+    // .nimbus.RequestRelay request_relay = 18;
+
+    pub fn request_relay(&self) -> &RequestRelay {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::RequestRelay(ref v)) => v,
+            _ => <RequestRelay as ::protobuf::Message>::default_instance(),
+        }
+    }
+
+    pub fn clear_request_relay(&mut self) {
+        self.union = ::std::option::Option::None;
+    }
+
+    pub fn has_request_relay(&self) -> bool {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::RequestRelay(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_request_relay(&mut self, v: RequestRelay) {
+        self.union = ::std::option::Option::Some(rendezvous_message::Union::RequestRelay(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_request_relay(&mut self) -> &mut RequestRelay {
+        if let ::std::option::Option::Some(rendezvous_message::Union::RequestRelay(_)) = self.union {
+        } else {
+            self.union = ::std::option::Option::Some(rendezvous_message::Union::RequestRelay(RequestRelay::new()));
+        }
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::RequestRelay(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_request_relay(&mut self) -> RequestRelay {
+        if self.has_request_relay() {
+            match self.union.take() {
+                ::std::option::Option::Some(rendezvous_message::Union::RequestRelay(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            RequestRelay::new()
+        }
+    }
+
+    // .nimbus.RelayResponse relay_response = 19;
+
+    pub fn relay_response(&self) -> &RelayResponse {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::RelayResponse(ref v)) => v,
+            _ => <RelayResponse as ::protobuf::Message>::default_instance(),
+        }
+    }
+
+    pub fn clear_relay_response(&mut self) {
+        self.union = ::std::option::Option::None;
+    }
+
+    pub fn has_relay_response(&self) -> bool {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::RelayResponse(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_relay_response(&mut self, v: RelayResponse) {
+        self.union = ::std::option::Option::Some(rendezvous_message::Union::RelayResponse(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_relay_response(&mut self) -> &mut RelayResponse {
+        if let ::std::option::Option::Some(rendezvous_message::Union::RelayResponse(_)) = self.union {
+        } else {
+            self.union = ::std::option::Option::Some(rendezvous_message::Union::RelayResponse(RelayResponse::new()));
+        }
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::RelayResponse(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_relay_response(&mut self) -> RelayResponse {
+        if self.has_relay_response() {
+            match self.union.take() {
+                ::std::option::Option::Some(rendezvous_message::Union::RelayResponse(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            RelayResponse::new()
+        }
+    }
+
     // .nimbus.TestNatRequest test_nat_request = 20;
 
     pub fn test_nat_request(&self) -> &TestNatRequest {
@@ -1589,8 +3859,155 @@ impl RendezvousMessage {
         }
     }
 
+    // .nimbus.OnlineRequest online_request = 23;
+
+    pub fn online_request(&self) -> &OnlineRequest {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::OnlineRequest(ref v)) => v,
+            _ => <OnlineRequest as ::protobuf::Message>::default_instance(),
+        }
+    }
+
+    pub fn clear_online_request(&mut self) {
+        self.union = ::std::option::Option::None;
+    }
+
+    pub fn has_online_request(&self) -> bool {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::OnlineRequest(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_online_request(&mut self, v: OnlineRequest) {
+        self.union = ::std::option::Option::Some(rendezvous_message::Union::OnlineRequest(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_online_request(&mut self) -> &mut OnlineRequest {
+        if let ::std::option::Option::Some(rendezvous_message::Union::OnlineRequest(_)) = self.union {
+        } else {
+            self.union = ::std::option::Option::Some(rendezvous_message::Union::OnlineRequest(OnlineRequest::new()));
+        }
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::OnlineRequest(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_online_request(&mut self) -> OnlineRequest {
+        if self.has_online_request() {
+            match self.union.take() {
+                ::std::option::Option::Some(rendezvous_message::Union::OnlineRequest(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            OnlineRequest::new()
+        }
+    }
+
+    // .nimbus.OnlineResponse online_response = 24;
+
+    pub fn online_response(&self) -> &OnlineResponse {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::OnlineResponse(ref v)) => v,
+            _ => <OnlineResponse as ::protobuf::Message>::default_instance(),
+        }
+    }
+
+    pub fn clear_online_response(&mut self) {
+        self.union = ::std::option::Option::None;
+    }
+
+    pub fn has_online_response(&self) -> bool {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::OnlineResponse(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_online_response(&mut self, v: OnlineResponse) {
+        self.union = ::std::option::Option::Some(rendezvous_message::Union::OnlineResponse(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_online_response(&mut self) -> &mut OnlineResponse {
+        if let ::std::option::Option::Some(rendezvous_message::Union::OnlineResponse(_)) = self.union {
+        } else {
+            self.union = ::std::option::Option::Some(rendezvous_message::Union::OnlineResponse(OnlineResponse::new()));
+        }
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::OnlineResponse(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_online_response(&mut self) -> OnlineResponse {
+        if self.has_online_response() {
+            match self.union.take() {
+                ::std::option::Option::Some(rendezvous_message::Union::OnlineResponse(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            OnlineResponse::new()
+        }
+    }
+
+    // .nimbus.HealthCheck hc = 26;
+
+    pub fn hc(&self) -> &HealthCheck {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::Hc(ref v)) => v,
+            _ => <HealthCheck as ::protobuf::Message>::default_instance(),
+        }
+    }
+
+    pub fn clear_hc(&mut self) {
+        self.union = ::std::option::Option::None;
+    }
+
+    pub fn has_hc(&self) -> bool {
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::Hc(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_hc(&mut self, v: HealthCheck) {
+        self.union = ::std::option::Option::Some(rendezvous_message::Union::Hc(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_hc(&mut self) -> &mut HealthCheck {
+        if let ::std::option::Option::Some(rendezvous_message::Union::Hc(_)) = self.union {
+        } else {
+            self.union = ::std::option::Option::Some(rendezvous_message::Union::Hc(HealthCheck::new()));
+        }
+        match self.union {
+            ::std::option::Option::Some(rendezvous_message::Union::Hc(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_hc(&mut self) -> HealthCheck {
+        if self.has_hc() {
+            match self.union.take() {
+                ::std::option::Option::Some(rendezvous_message::Union::Hc(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            HealthCheck::new()
+        }
+    }
+
     fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
-        let mut fields = ::std::vec::Vec::with_capacity(7);
+        let mut fields = ::std::vec::Vec::with_capacity(18);
         let mut oneofs = ::std::vec::Vec::with_capacity(1);
         fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, RegisterPeer>(
             "register_peer",
@@ -1606,6 +4023,48 @@ impl RendezvousMessage {
             RendezvousMessage::mut_register_peer_response,
             RendezvousMessage::set_register_peer_response,
         ));
+        fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, PunchHoleRequest>(
+            "punch_hole_request",
+            RendezvousMessage::has_punch_hole_request,
+            RendezvousMessage::punch_hole_request,
+            RendezvousMessage::mut_punch_hole_request,
+            RendezvousMessage::set_punch_hole_request,
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, PunchHole>(
+            "punch_hole",
+            RendezvousMessage::has_punch_hole,
+            RendezvousMessage::punch_hole,
+            RendezvousMessage::mut_punch_hole,
+            RendezvousMessage::set_punch_hole,
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, PunchHoleSent>(
+            "punch_hole_sent",
+            RendezvousMessage::has_punch_hole_sent,
+            RendezvousMessage::punch_hole_sent,
+            RendezvousMessage::mut_punch_hole_sent,
+            RendezvousMessage::set_punch_hole_sent,
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, PunchHoleResponse>(
+            "punch_hole_response",
+            RendezvousMessage::has_punch_hole_response,
+            RendezvousMessage::punch_hole_response,
+            RendezvousMessage::mut_punch_hole_response,
+            RendezvousMessage::set_punch_hole_response,
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, FetchLocalAddr>(
+            "fetch_local_addr",
+            RendezvousMessage::has_fetch_local_addr,
+            RendezvousMessage::fetch_local_addr,
+            RendezvousMessage::mut_fetch_local_addr,
+            RendezvousMessage::set_fetch_local_addr,
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, LocalAddr>(
+            "local_addr",
+            RendezvousMessage::has_local_addr,
+            RendezvousMessage::local_addr,
+            RendezvousMessage::mut_local_addr,
+            RendezvousMessage::set_local_addr,
+        ));
         fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, ConfigUpdate>(
             "configure_update",
             RendezvousMessage::has_configure_update,
@@ -1627,6 +4086,20 @@ impl RendezvousMessage {
             RendezvousMessage::mut_register_pk_response,
             RendezvousMessage::set_register_pk_response,
         ));
+        fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, RequestRelay>(
+            "request_relay",
+            RendezvousMessage::has_request_relay,
+            RendezvousMessage::request_relay,
+            RendezvousMessage::mut_request_relay,
+            RendezvousMessage::set_request_relay,
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, RelayResponse>(
+            "relay_response",
+            RendezvousMessage::has_relay_response,
+            RendezvousMessage::relay_response,
+            RendezvousMessage::mut_relay_response,
+            RendezvousMessage::set_relay_response,
+        ));
         fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, TestNatRequest>(
             "test_nat_request",
             RendezvousMessage::has_test_nat_request,
@@ -1641,6 +4114,27 @@ impl RendezvousMessage {
             RendezvousMessage::mut_test_nat_response,
             RendezvousMessage::set_test_nat_response,
         ));
+        fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, OnlineRequest>(
+            "online_request",
+            RendezvousMessage::has_online_request,
+            RendezvousMessage::online_request,
+            RendezvousMessage::mut_online_request,
+            RendezvousMessage::set_online_request,
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, OnlineResponse>(
+            "online_response",
+            RendezvousMessage::has_online_response,
+            RendezvousMessage::online_response,
+            RendezvousMessage::mut_online_response,
+            RendezvousMessage::set_online_response,
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_oneof_message_has_get_mut_set_accessor::<_, HealthCheck>(
+            "hc",
+            RendezvousMessage::has_hc,
+            RendezvousMessage::hc,
+            RendezvousMessage::mut_hc,
+            RendezvousMessage::set_hc,
+        ));
         oneofs.push(rendezvous_message::Union::generated_oneof_descriptor_data());
         ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<RendezvousMessage>(
             "RendezvousMessage",
@@ -1666,6 +4160,24 @@ impl ::protobuf::Message for RendezvousMessage {
                 58 => {
                     self.union = ::std::option::Option::Some(rendezvous_message::Union::RegisterPeerResponse(is.read_message()?));
                 },
+                66 => {
+                    self.union = ::std::option::Option::Some(rendezvous_message::Union::PunchHoleRequest(is.read_message()?));
+                },
+                74 => {
+                    self.union = ::std::option::Option::Some(rendezvous_message::Union::PunchHole(is.read_message()?));
+                },
+                82 => {
+                    self.union = ::std::option::Option::Some(rendezvous_message::Union::PunchHoleSent(is.read_message()?));
+                },
+                90 => {
+                    self.union = ::std::option::Option::Some(rendezvous_message::Union::PunchHoleResponse(is.read_message()?));
+                },
+                98 => {
+                    self.union = ::std::option::Option::Some(rendezvous_message::Union::FetchLocalAddr(is.read_message()?));
+                },
+                106 => {
+                    self.union = ::std::option::Option::Some(rendezvous_message::Union::LocalAddr(is.read_message()?));
+                },
                 114 => {
                     self.union = ::std::option::Option::Some(rendezvous_message::Union::ConfigureUpdate(is.read_message()?));
                 },
@@ -1675,12 +4187,27 @@ impl ::protobuf::Message for RendezvousMessage {
                 130 => {
                     self.union = ::std::option::Option::Some(rendezvous_message::Union::RegisterPkResponse(is.read_message()?));
                 },
+                146 => {
+                    self.union = ::std::option::Option::Some(rendezvous_message::Union::RequestRelay(is.read_message()?));
+                },
+                154 => {
+                    self.union = ::std::option::Option::Some(rendezvous_message::Union::RelayResponse(is.read_message()?));
+                },
                 162 => {
                     self.union = ::std::option::Option::Some(rendezvous_message::Union::TestNatRequest(is.read_message()?));
                 },
                 170 => {
                     self.union = ::std::option::Option::Some(rendezvous_message::Union::TestNatResponse(is.read_message()?));
                 },
+                186 => {
+                    self.union = ::std::option::Option::Some(rendezvous_message::Union::OnlineRequest(is.read_message()?));
+                },
+                194 => {
+                    self.union = ::std::option::Option::Some(rendezvous_message::Union::OnlineResponse(is.read_message()?));
+                },
+                210 => {
+                    self.union = ::std::option::Option::Some(rendezvous_message::Union::Hc(is.read_message()?));
+                },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
                 },
@@ -1703,6 +4230,30 @@ impl ::protobuf::Message for RendezvousMessage {
                     let len = v.compute_size();
                     my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
                 },
+                &rendezvous_message::Union::PunchHoleRequest(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
+                },
+                &rendezvous_message::Union::PunchHole(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
+                },
+                &rendezvous_message::Union::PunchHoleSent(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
+                },
+                &rendezvous_message::Union::PunchHoleResponse(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
+                },
+                &rendezvous_message::Union::FetchLocalAddr(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
+                },
+                &rendezvous_message::Union::LocalAddr(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
+                },
                 &rendezvous_message::Union::ConfigureUpdate(ref v) => {
                     let len = v.compute_size();
                     my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
@@ -1715,6 +4266,14 @@ impl ::protobuf::Message for RendezvousMessage {
                     let len = v.compute_size();
                     my_size += 2 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
                 },
+                &rendezvous_message::Union::RequestRelay(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 2 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
+                },
+                &rendezvous_message::Union::RelayResponse(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 2 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
+                },
                 &rendezvous_message::Union::TestNatRequest(ref v) => {
                     let len = v.compute_size();
                     my_size += 2 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
@@ -1723,6 +4282,18 @@ impl ::protobuf::Message for RendezvousMessage {
                     let len = v.compute_size();
                     my_size += 2 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
                 },
+                &rendezvous_message::Union::OnlineRequest(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 2 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
+                },
+                &rendezvous_message::Union::OnlineResponse(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 2 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
+                },
+                &rendezvous_message::Union::Hc(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 2 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
+                },
             };
         }
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
@@ -1739,6 +4310,24 @@ impl ::protobuf::Message for RendezvousMessage {
                 &rendezvous_message::Union::RegisterPeerResponse(ref v) => {
                     ::protobuf::rt::write_message_field_with_cached_size(7, v, os)?;
                 },
+                &rendezvous_message::Union::PunchHoleRequest(ref v) => {
+                    ::protobuf::rt::write_message_field_with_cached_size(8, v, os)?;
+                },
+                &rendezvous_message::Union::PunchHole(ref v) => {
+                    ::protobuf::rt::write_message_field_with_cached_size(9, v, os)?;
+                },
+                &rendezvous_message::Union::PunchHoleSent(ref v) => {
+                    ::protobuf::rt::write_message_field_with_cached_size(10, v, os)?;
+                },
+                &rendezvous_message::Union::PunchHoleResponse(ref v) => {
+                    ::protobuf::rt::write_message_field_with_cached_size(11, v, os)?;
+                },
+                &rendezvous_message::Union::FetchLocalAddr(ref v) => {
+                    ::protobuf::rt::write_message_field_with_cached_size(12, v, os)?;
+                },
+                &rendezvous_message::Union::LocalAddr(ref v) => {
+                    ::protobuf::rt::write_message_field_with_cached_size(13, v, os)?;
+                },
                 &rendezvous_message::Union::ConfigureUpdate(ref v) => {
                     ::protobuf::rt::write_message_field_with_cached_size(14, v, os)?;
                 },
@@ -1748,12 +4337,27 @@ impl ::protobuf::Message for RendezvousMessage {
                 &rendezvous_message::Union::RegisterPkResponse(ref v) => {
                     ::protobuf::rt::write_message_field_with_cached_size(16, v, os)?;
                 },
+                &rendezvous_message::Union::RequestRelay(ref v) => {
+                    ::protobuf::rt::write_message_field_with_cached_size(18, v, os)?;
+                },
+                &rendezvous_message::Union::RelayResponse(ref v) => {
+                    ::protobuf::rt::write_message_field_with_cached_size(19, v, os)?;
+                },
                 &rendezvous_message::Union::TestNatRequest(ref v) => {
                     ::protobuf::rt::write_message_field_with_cached_size(20, v, os)?;
                 },
                 &rendezvous_message::Union::TestNatResponse(ref v) => {
                     ::protobuf::rt::write_message_field_with_cached_size(21, v, os)?;
                 },
+                &rendezvous_message::Union::OnlineRequest(ref v) => {
+                    ::protobuf::rt::write_message_field_with_cached_size(23, v, os)?;
+                },
+                &rendezvous_message::Union::OnlineResponse(ref v) => {
+                    ::protobuf::rt::write_message_field_with_cached_size(24, v, os)?;
+                },
+                &rendezvous_message::Union::Hc(ref v) => {
+                    ::protobuf::rt::write_message_field_with_cached_size(26, v, os)?;
+                },
             };
         }
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
@@ -1773,6 +4377,17 @@ impl ::protobuf::Message for RendezvousMessage {
     }
 
     fn clear(&mut self) {
+        self.union = ::std::option::Option::None;
+        self.union = ::std::option::Option::None;
+        self.union = ::std::option::Option::None;
+        self.union = ::std::option::Option::None;
+        self.union = ::std::option::Option::None;
+        self.union = ::std::option::Option::None;
+        self.union = ::std::option::Option::None;
+        self.union = ::std::option::Option::None;
+        self.union = ::std::option::Option::None;
+        self.union = ::std::option::Option::None;
+        self.union = ::std::option::Option::None;
         self.union = ::std::option::Option::None;
         self.union = ::std::option::Option::None;
         self.union = ::std::option::Option::None;
@@ -1820,16 +4435,38 @@ pub mod rendezvous_message {
         RegisterPeer(super::RegisterPeer),
         // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.register_peer_response)
         RegisterPeerResponse(super::RegisterPeerResponse),
+        // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.punch_hole_request)
+        PunchHoleRequest(super::PunchHoleRequest),
+        // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.punch_hole)
+        PunchHole(super::PunchHole),
+        // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.punch_hole_sent)
+        PunchHoleSent(super::PunchHoleSent),
+        // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.punch_hole_response)
+        PunchHoleResponse(super::PunchHoleResponse),
+        // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.fetch_local_addr)
+        FetchLocalAddr(super::FetchLocalAddr),
+        // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.local_addr)
+        LocalAddr(super::LocalAddr),
         // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.configure_update)
         ConfigureUpdate(super::ConfigUpdate),
         // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.register_pk)
         RegisterPk(super::RegisterPk),
         // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.register_pk_response)
         RegisterPkResponse(super::RegisterPkResponse),
+        // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.request_relay)
+        RequestRelay(super::RequestRelay),
+        // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.relay_response)
+        RelayResponse(super::RelayResponse),
         // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.test_nat_request)
         TestNatRequest(super::TestNatRequest),
         // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.test_nat_response)
         TestNatResponse(super::TestNatResponse),
+        // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.online_request)
+        OnlineRequest(super::OnlineRequest),
+        // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.online_response)
+        OnlineResponse(super::OnlineResponse),
+        // @@protoc_insertion_point(oneof_field:nimbus.RendezvousMessage.hc)
+        Hc(super::HealthCheck),
     }
 
     impl ::protobuf::Oneof for Union {
@@ -1923,31 +4560,78 @@ static file_descriptor_proto_data: &'static [u8] = b"\
     \x20\x01(\x08R\trequestPk\"W\n\nRegisterPk\x12\x0e\n\x02id\x18\x01\x20\
     \x01(\tR\x02id\x12\x12\n\x04uuid\x18\x02\x20\x01(\x0cR\x04uuid\x12\x0e\n\
     \x02pk\x18\x03\x20\x01(\x0cR\x02pk\x12\x15\n\x06old_id\x18\x04\x20\x01(\
-    \tR\x05oldId\"\xcf\x01\n\x12RegisterPkResponse\x129\n\x06result\x18\x01\
-    \x20\x01(\x0e2!.nimbus.RegisterPkResponse.ResultR\x06result\"~\n\x06Resu\
-    lt\x12\x06\n\x02OK\x10\0\x12\x11\n\rUUID_MISMATCH\x10\x02\x12\r\n\tID_EX\
-    ISTS\x10\x03\x12\x10\n\x0cTOO_FREQUENT\x10\x04\x12\x15\n\x11INVALID_ID_F\
-    ORMAT\x10\x05\x12\x0f\n\x0bNOT_SUPPORT\x10\x06\x12\x10\n\x0cSERVER_ERROR\
-    \x10\x07\"{\n\tPunchHole\x12\x1f\n\x0bsocket_addr\x18\x01\x20\x01(\x0cR\
-    \nsocketAddr\x12!\n\x0crelay_server\x18\x02\x20\x01(\tR\x0brelayServer\
-    \x12*\n\x08nat_type\x18\x03\x20\x01(\x0e2\x0f.nimbus.NatTypeR\x07natType\
-    \"U\n\x0cConfigUpdate\x12\x16\n\x06serial\x18\x01\x20\x01(\x05R\x06seria\
-    l\x12-\n\x12rendezvous_servers\x18\x02\x20\x03(\tR\x11rendezvousServers\
-    \"(\n\x0eTestNatRequest\x12\x16\n\x06serial\x18\x01\x20\x01(\x05R\x06ser\
-    ial\"%\n\x0fTestNatResponse\x12\x12\n\x04port\x18\x01\x20\x01(\x05R\x04p\
-    ort\"\x84\x04\n\x11RendezvousMessage\x12;\n\rregister_peer\x18\x06\x20\
-    \x01(\x0b2\x14.nimbus.RegisterPeerH\0R\x0cregisterPeer\x12T\n\x16registe\
-    r_peer_response\x18\x07\x20\x01(\x0b2\x1c.nimbus.RegisterPeerResponseH\0\
-    R\x14registerPeerResponse\x12A\n\x10configure_update\x18\x0e\x20\x01(\
-    \x0b2\x14.nimbus.ConfigUpdateH\0R\x0fconfigureUpdate\x125\n\x0bregister_\
-    pk\x18\x0f\x20\x01(\x0b2\x12.nimbus.RegisterPkH\0R\nregisterPk\x12N\n\
-    \x14register_pk_response\x18\x10\x20\x01(\x0b2\x1a.nimbus.RegisterPkResp\
-    onseH\0R\x12registerPkResponse\x12B\n\x10test_nat_request\x18\x14\x20\
-    \x01(\x0b2\x16.nimbus.TestNatRequestH\0R\x0etestNatRequest\x12E\n\x11tes\
-    t_nat_response\x18\x15\x20\x01(\x0b2\x17.nimbus.TestNatResponseH\0R\x0ft\
-    estNatResponseB\x07\n\x05union*9\n\x07NatType\x12\x0f\n\x0bUNKNOWN_NAT\
-    \x10\0\x12\x0e\n\nASYMMETRIC\x10\x01\x12\r\n\tSYMMETRIC\x10\x02b\x06prot\
-    o3\
+    \tR\x05oldId\"\xde\x01\n\x12RegisterPkResponse\x129\n\x06result\x18\x01\
+    \x20\x01(\x0e2!.nimbus.RegisterPkResponse.ResultR\x06result\"\x8c\x01\n\
+    \x06Result\x12\x06\n\x02OK\x10\0\x12\x11\n\rUUID_MISMATCH\x10\x02\x12\r\
+    \n\tID_EXISTS\x10\x03\x12\x10\n\x0cTOO_FREQUENT\x10\x04\x12\x15\n\x11INV\
+    ALID_ID_FORMAT\x10\x05\x12\x0f\n\x0bNOT_SUPPORT\x10\x06\x12\x10\n\x0cSER\
+    VER_ERROR\x10\x07\x12\x0c\n\x08DISABLED\x10\x08\"o\n\x10PunchHoleRequest\
+    \x12\x0e\n\x02id\x18\x01\x20\x01(\tR\x02id\x12*\n\x08nat_type\x18\x02\
+    \x20\x01(\x0e2\x0f.nimbus.NatTypeR\x07natType\x12\x1f\n\x0blicence_key\
+    \x18\x03\x20\x01(\tR\nlicenceKey\"{\n\tPunchHole\x12\x1f\n\x0bsocket_add\
+    r\x18\x01\x20\x01(\x0cR\nsocketAddr\x12!\n\x0crelay_server\x18\x02\x20\
+    \x01(\tR\x0brelayServer\x12*\n\x08nat_type\x18\x03\x20\x01(\x0e2\x0f.nim\
+    bus.NatTypeR\x07natType\"\x8f\x01\n\rPunchHoleSent\x12\x1f\n\x0bsocket_a\
+    ddr\x18\x01\x20\x01(\x0cR\nsocketAddr\x12\x0e\n\x02id\x18\x02\x20\x01(\t\
+    R\x02id\x12!\n\x0crelay_server\x18\x03\x20\x01(\tR\x0brelayServer\x12*\n\
+    \x08nat_type\x18\x04\x20\x01(\x0e2\x0f.nimbus.NatTypeR\x07natType\"\xc9\
+    \x02\n\x11PunchHoleResponse\x12\x1f\n\x0bsocket_addr\x18\x01\x20\x01(\
+    \x0cR\nsocketAddr\x12\x0e\n\x02pk\x18\x02\x20\x01(\x0cR\x02pk\x12;\n\x07\
+    failure\x18\x03\x20\x01(\x0e2!.nimbus.PunchHoleResponse.FailureR\x07fail\
+    ure\x12!\n\x0crelay_server\x18\x04\x20\x01(\tR\x0brelayServer\x12*\n\x08\
+    nat_type\x18\x05\x20\x01(\x0e2\x0f.nimbus.NatTypeR\x07natType\x12\x19\n\
+    \x08is_local\x18\x06\x20\x01(\x08R\x07isLocal\"\\\n\x07Failure\x12\x0e\n\
+    \nNO_FAILURE\x10\0\x12\x10\n\x0cID_NOT_EXIST\x10\x01\x12\x0b\n\x07OFFLIN\
+    E\x10\x02\x12\x14\n\x10LICENSE_MISMATCH\x10\x03\x12\x0c\n\x08DISABLED\
+    \x10\x04\"T\n\x0eFetchLocalAddr\x12\x1f\n\x0bsocket_addr\x18\x01\x20\x01\
+    (\x0cR\nsocketAddr\x12!\n\x0crelay_server\x18\x02\x20\x01(\tR\x0brelaySe\
+    rver\"~\n\tLocalAddr\x12\x1f\n\x0bsocket_addr\x18\x01\x20\x01(\x0cR\nsoc\
+    ketAddr\x12\x1d\n\nlocal_addr\x18\x02\x20\x01(\x0cR\tlocalAddr\x12!\n\
+    \x0crelay_server\x18\x03\x20\x01(\tR\x0brelayServer\x12\x0e\n\x02id\x18\
+    \x04\x20\x01(\tR\x02id\"\x97\x01\n\x0cRequestRelay\x12\x0e\n\x02id\x18\
+    \x01\x20\x01(\tR\x02id\x12\x12\n\x04uuid\x18\x02\x20\x01(\tR\x04uuid\x12\
+    \x1f\n\x0bsocket_addr\x18\x03\x20\x01(\x0cR\nsocketAddr\x12!\n\x0crelay_\
+    server\x18\x04\x20\x01(\tR\x0brelayServer\x12\x1f\n\x0blicence_key\x18\
+    \x06\x20\x01(\tR\nlicenceKey\"\xac\x01\n\rRelayResponse\x12\x1f\n\x0bsoc\
+    ket_addr\x18\x01\x20\x01(\x0cR\nsocketAddr\x12\x12\n\x04uuid\x18\x02\x20\
+    \x01(\tR\x04uuid\x12!\n\x0crelay_server\x18\x03\x20\x01(\tR\x0brelayServ\
+    er\x12\x0e\n\x02id\x18\x04\x20\x01(\tR\x02id\x12\x0e\n\x02pk\x18\x05\x20\
+    \x01(\x0cR\x02pk\x12#\n\rrefuse_reason\x18\x06\x20\x01(\tR\x0crefuseReas\
+    on\"&\n\x04IdPk\x12\x0e\n\x02id\x18\x01\x20\x01(\tR\x02id\x12\x0e\n\x02p\
+    k\x18\x02\x20\x01(\x0cR\x02pk\"U\n\x0cConfigUpdate\x12\x16\n\x06serial\
+    \x18\x01\x20\x01(\x05R\x06serial\x12-\n\x12rendezvous_servers\x18\x02\
+    \x20\x03(\tR\x11rendezvousServers\"(\n\x0eTestNatRequest\x12\x16\n\x06se\
+    rial\x18\x01\x20\x01(\x05R\x06serial\"%\n\x0fTestNatResponse\x12\x12\n\
+    \x04port\x18\x01\x20\x01(\x05R\x04port\"5\n\rOnlineRequest\x12\x0e\n\x02\
+    id\x18\x01\x20\x01(\tR\x02id\x12\x14\n\x05peers\x18\x02\x20\x03(\tR\x05p\
+    eers\"(\n\x0eOnlineResponse\x12\x16\n\x06states\x18\x01\x20\x01(\x0cR\
+    \x06states\"#\n\x0bHealthCheck\x12\x14\n\x05token\x18\x01\x20\x01(\tR\
+    \x05token\"\xaf\t\n\x11RendezvousMessage\x12;\n\rregister_peer\x18\x06\
+    \x20\x01(\x0b2\x14.nimbus.RegisterPeerH\0R\x0cregisterPeer\x12T\n\x16reg\
+    ister_peer_response\x18\x07\x20\x01(\x0b2\x1c.nimbus.RegisterPeerRespons\
+    eH\0R\x14registerPeerResponse\x12H\n\x12punch_hole_request\x18\x08\x20\
+    \x01(\x0b2\x18.nimbus.PunchHoleRequestH\0R\x10punchHoleRequest\x122\n\np\
+    unch_hole\x18\t\x20\x01(\x0b2\x11.nimbus.PunchHoleH\0R\tpunchHole\x12?\n\
+    \x0fpunch_hole_sent\x18\n\x20\x01(\x0b2\x15.nimbus.PunchHoleSentH\0R\rpu\
+    nchHoleSent\x12K\n\x13punch_hole_response\x18\x0b\x20\x01(\x0b2\x19.nimb\
+    us.PunchHoleResponseH\0R\x11punchHoleResponse\x12B\n\x10fetch_local_addr\
+    \x18\x0c\x20\x01(\x0b2\x16.nimbus.FetchLocalAddrH\0R\x0efetchLocalAddr\
+    \x122\n\nlocal_addr\x18\r\x20\x01(\x0b2\x11.nimbus.LocalAddrH\0R\tlocalA\
+    ddr\x12A\n\x10configure_update\x18\x0e\x20\x01(\x0b2\x14.nimbus.ConfigUp\
+    dateH\0R\x0fconfigureUpdate\x125\n\x0bregister_pk\x18\x0f\x20\x01(\x0b2\
+    \x12.nimbus.RegisterPkH\0R\nregisterPk\x12N\n\x14register_pk_response\
+    \x18\x10\x20\x01(\x0b2\x1a.nimbus.RegisterPkResponseH\0R\x12registerPkRe\
+    sponse\x12;\n\rrequest_relay\x18\x12\x20\x01(\x0b2\x14.nimbus.RequestRel\
+    ayH\0R\x0crequestRelay\x12>\n\x0erelay_response\x18\x13\x20\x01(\x0b2\
+    \x15.nimbus.RelayResponseH\0R\rrelayResponse\x12B\n\x10test_nat_request\
+    \x18\x14\x20\x01(\x0b2\x16.nimbus.TestNatRequestH\0R\x0etestNatRequest\
+    \x12E\n\x11test_nat_response\x18\x15\x20\x01(\x0b2\x17.nimbus.TestNatRes\
+    ponseH\0R\x0ftestNatResponse\x12>\n\x0eonline_request\x18\x17\x20\x01(\
+    \x0b2\x15.nimbus.OnlineRequestH\0R\ronlineRequest\x12A\n\x0fonline_respo\
+    nse\x18\x18\x20\x01(\x0b2\x16.nimbus.OnlineResponseH\0R\x0eonlineRespons\
+    e\x12%\n\x02hc\x18\x1a\x20\x01(\x0b2\x13.nimbus.HealthCheckH\0R\x02hcB\
+    \x07\n\x05union*9\n\x07NatType\x12\x0f\n\x0bUNKNOWN_NAT\x10\0\x12\x0e\n\
+    \nASYMMETRIC\x10\x01\x12\r\n\tSYMMETRIC\x10\x02b\x06proto3\
 ";
 
 /// `FileDescriptorProto` object which was a source for this generated file
@@ -1965,19 +4649,31 @@ pub fn file_descriptor() -> &'static ::protobuf::reflect::FileDescriptor {
     file_descriptor.get(|| {
         let generated_file_descriptor = generated_file_descriptor_lazy.get(|| {
             let mut deps = ::std::vec::Vec::with_capacity(0);
-            let mut messages = ::std::vec::Vec::with_capacity(9);
+            let mut messages = ::std::vec::Vec::with_capacity(20);
             messages.push(RegisterPeer::generated_message_descriptor_data());
             messages.push(RegisterPeerResponse::generated_message_descriptor_data());
             messages.push(RegisterPk::generated_message_descriptor_data());
             messages.push(RegisterPkResponse::generated_message_descriptor_data());
+            messages.push(PunchHoleRequest::generated_message_descriptor_data());
             messages.push(PunchHole::generated_message_descriptor_data());
+            messages.push(PunchHoleSent::generated_message_descriptor_data());
+            messages.push(PunchHoleResponse::generated_message_descriptor_data());
+            messages.push(FetchLocalAddr::generated_message_descriptor_data());
+            messages.push(LocalAddr::generated_message_descriptor_data());
+            messages.push(RequestRelay::generated_message_descriptor_data());
+            messages.push(RelayResponse::generated_message_descriptor_data());
+            messages.push(IdPk::generated_message_descriptor_data());
             messages.push(ConfigUpdate::generated_message_descriptor_data());
             messages.push(TestNatRequest::generated_message_descriptor_data());
             messages.push(TestNatResponse::generated_message_descriptor_data());
+            messages.push(OnlineRequest::generated_message_descriptor_data());
+            messages.push(OnlineResponse::generated_message_descriptor_data());
+            messages.push(HealthCheck::generated_message_descriptor_data());
             messages.push(RendezvousMessage::generated_message_descriptor_data());
-            let mut enums = ::std::vec::Vec::with_capacity(2);
+            let mut enums = ::std::vec::Vec::with_capacity(3);
             enums.push(NatType::generated_enum_descriptor_data());
             enums.push(register_pk_response::Result::generated_enum_descriptor_data());
+            enums.push(punch_hole_response::Failure::generated_enum_descriptor_data());
             ::protobuf::reflect::GeneratedFileDescriptor::new_generated(
                 file_descriptor_proto(),
                 deps,
@@ -0,0 +1,299 @@
+use std::{
+  fmt,
+  net::IpAddr,
+  str::FromStr,
+  sync::{Mutex, RwLock},
+};
+
+use nimbus_common::{
+  anyhow::{anyhow, Context},
+  logger::*,
+  ResultType,
+};
+
+use crate::{common::ip_to_u128, config::AccessConfig};
+
+/// An ipv4 or ipv6 network, e.g. `10.0.0.0/8` or `2001:db8::/32`, a bare
+/// ip is a single address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNet {
+  ip: IpAddr,
+  prefix: u8,
+}
+
+impl IpNet {
+  /// Whether the ip is in the network, ipv4-mapped ipv6 as ipv4.
+  pub fn contains(&self, ip: IpAddr) -> bool {
+    let (ip, net) = (ip_to_u128(ip), ip_to_u128(self.ip));
+    let prefix = match self.ip {
+      IpAddr::V4(_) => self.prefix as u32 + 96,
+      IpAddr::V6(_) => self.prefix as u32,
+    };
+    let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+    ip & mask == net & mask
+  }
+}
+
+impl FromStr for IpNet {
+  type Err = nimbus_common::anyhow::Error;
+
+  fn from_str(s: &str) -> ResultType<IpNet> {
+    let (ip, prefix) = match s.split_once('/') {
+      Some((ip, prefix)) => (ip, Some(prefix)),
+      None => (s, None),
+    };
+    let ip: IpAddr = ip
+      .parse()
+      .with_context(|| format!("invalid network {}", s))?;
+    let max = if ip.is_ipv4() { 32 } else { 128 };
+    let prefix = match prefix {
+      Some(prefix) => prefix
+        .parse::<u8>()
+        .ok()
+        .filter(|x| *x <= max)
+        .ok_or_else(|| anyhow!("invalid prefix length of {}", s))?,
+      None => max,
+    };
+    Ok(IpNet { ip, prefix })
+  }
+}
+
+impl fmt::Display for IpNet {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}/{}", self.ip, self.prefix)
+  }
+}
+
+#[derive(Debug, Default, Clone)]
+struct Lists {
+  allow: Vec<IpNet>,
+  deny: Vec<IpNet>,
+}
+
+/// Which ips can reach the listeners and the udp socket.
+///
+/// An ip is denied if it is in a deny list, or if there is an allow list
+/// and it is not in it. The loopback addresses are always allowed, for the
+/// admin console.
+///
+/// The lists of the config are extended by the admin console, the
+/// additions are saved in `AccessConfig::file` as `allow <net>` or
+/// `deny <net>` lines.
+#[derive(Debug)]
+pub struct AccessPolicy {
+  config: Lists,
+  manual: RwLock<Lists>,
+  /// one console update at a time, `check` only waits for the swap
+  updating: Mutex<()>,
+  file: String,
+}
+
+impl AccessPolicy {
+  pub fn new(config: &AccessConfig) -> ResultType<AccessPolicy> {
+    let parse = |nets: &[String]| {
+      nets
+        .iter()
+        .map(|x| x.parse())
+        .collect::<ResultType<Vec<IpNet>>>()
+    };
+    let manual = if config.file.is_empty() {
+      Lists::default()
+    } else {
+      match std::fs::read_to_string(&config.file) {
+        Ok(content) => parse_file(&content)
+          .with_context(|| format!("invalid {}", config.file))?,
+        // nothing added yet
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+          Lists::default()
+        }
+        Err(err) => {
+          return Err(err)
+            .with_context(|| format!("failed to read {}", config.file))
+        }
+      }
+    };
+    Ok(AccessPolicy {
+      config: Lists {
+        allow: parse(&config.allow)?,
+        deny: parse(&config.deny)?,
+      },
+      manual: RwLock::new(manual),
+      updating: Mutex::new(()),
+      file: config.file.clone(),
+    })
+  }
+
+  pub fn check(&self, ip: IpAddr) -> bool {
+    if ip.to_canonical().is_loopback() {
+      return true;
+    }
+    let manual = self.manual.read().unwrap();
+    if self
+      .config
+      .deny
+      .iter()
+      .chain(manual.deny.iter())
+      .any(|x| x.contains(ip))
+    {
+      return false;
+    }
+    let mut allow = self.config.allow.iter().chain(manual.allow.iter());
+    let no_allow_list = self.config.allow.is_empty() && manual.allow.is_empty();
+    no_allow_list || allow.any(|x| x.contains(ip))
+  }
+
+  /// Add (or remove) a network of the console, `deny` for a ban, and save
+  /// the additions.
+  ///
+  /// The lists are only changed once saved.
+  pub fn update(&self, deny: bool, net: IpNet, remove: bool) -> ResultType<()> {
+    let _updating = self.updating.lock().unwrap();
+    let mut manual = self.manual.read().unwrap().clone();
+    let list = if deny {
+      &mut manual.deny
+    } else {
+      &mut manual.allow
+    };
+    list.retain(|x| *x != net);
+    if !remove {
+      list.push(net);
+    }
+    if !self.file.is_empty() {
+      let mut content = String::new();
+      for (kind, list) in [("allow", &manual.allow), ("deny", &manual.deny)] {
+        for net in list {
+          content.push_str(&format!("{} {}\n", kind, net));
+        }
+      }
+      std::fs::write(&self.file, content)
+        .with_context(|| format!("failed to write {}", self.file))?;
+    }
+    *self.manual.write().unwrap() = manual;
+    info!(
+      "{} {} {}",
+      if remove { "removed" } else { "added" },
+      if deny { "deny" } else { "allow" },
+      net
+    );
+    Ok(())
+  }
+
+  /// The networks of the lists, `(deny, net, from the console)`.
+  pub fn list(&self) -> Vec<(bool, IpNet, bool)> {
+    let manual = self.manual.read().unwrap();
+    let mut res = Vec::new();
+    for (lists, is_manual) in [(&self.config, false), (&*manual, true)] {
+      res.extend(lists.allow.iter().map(|x| (false, *x, is_manual)));
+      res.extend(lists.deny.iter().map(|x| (true, *x, is_manual)));
+    }
+    res
+  }
+}
+
+fn parse_file(content: &str) -> ResultType<Lists> {
+  let mut lists = Lists::default();
+  for (i, line) in content.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let (list, net) = match line.split_once(' ') {
+      Some(("allow", net)) => (&mut lists.allow, net),
+      Some(("deny", net)) => (&mut lists.deny, net),
+      _ => return Err(anyhow!("line {}: {}", i + 1, line)),
+    };
+    list.push(
+      net
+        .trim()
+        .parse()
+        .with_context(|| format!("line {}: {}", i + 1, line))?,
+    );
+  }
+  Ok(lists)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::peer_store::tests::temp_path;
+
+  #[test]
+  fn test_ip_net() {
+    let net = |x: &str| x.parse::<IpNet>().unwrap();
+    let ip = |x: &str| x.parse::<IpAddr>().unwrap();
+    assert!(net("10.0.0.0/8").contains(ip("10.1.2.3")));
+    assert!(net("10.0.0.0/8").contains(ip("::ffff:10.1.2.3")));
+    assert!(!net("10.0.0.0/8").contains(ip("11.0.0.1")));
+    assert!(net("1.2.3.4").contains(ip("1.2.3.4")));
+    assert!(!net("1.2.3.4").contains(ip("1.2.3.5")));
+    assert!(net("0.0.0.0/0").contains(ip("8.8.8.8")));
+    assert!(!net("0.0.0.0/0").contains(ip("2001:db8::1")));
+    assert!(net("2001:db8::/32").contains(ip("2001:db8:ffff::1")));
+    assert!(!net("2001:db8::/32").contains(ip("2001:db9::1")));
+    assert_eq!(net("1.2.3.4").to_string(), "1.2.3.4/32");
+    assert!("1.2.3.4/33".parse::<IpNet>().is_err());
+    assert!("1.2.3/24".parse::<IpNet>().is_err());
+  }
+
+  #[test]
+  fn test_access_policy() {
+    let file = temp_path("access.txt");
+    let config = AccessConfig {
+      allow: vec!["10.0.0.0/8".to_owned(), "2001:db8::/32".to_owned()],
+      deny: vec!["10.0.0.0/24".to_owned()],
+      file: file.clone(),
+    };
+    let ip = |x: &str| x.parse::<IpAddr>().unwrap();
+    let policy = AccessPolicy::new(&config).unwrap();
+    assert!(policy.check(ip("10.1.0.1")));
+    assert!(policy.check(ip("2001:db8::1")));
+    assert!(!policy.check(ip("10.0.0.1")));
+    assert!(!policy.check(ip("8.8.8.8")));
+    assert!(policy.check(ip("127.0.0.1")));
+
+    policy
+      .update(true, "10.1.0.0/16".parse().unwrap(), false)
+      .unwrap();
+    policy
+      .update(false, "8.8.8.8".parse().unwrap(), false)
+      .unwrap();
+    assert!(!policy.check(ip("10.1.0.1")));
+    assert!(policy.check(ip("8.8.8.8")));
+    assert_eq!(policy.list().len(), 5);
+
+    // the console additions are kept
+    let policy = AccessPolicy::new(&config).unwrap();
+    assert!(!policy.check(ip("10.1.0.1")));
+    assert!(policy.check(ip("8.8.8.8")));
+    policy
+      .update(true, "10.1.0.0/16".parse().unwrap(), true)
+      .unwrap();
+    let policy = AccessPolicy::new(&config).unwrap();
+    assert!(policy.check(ip("10.1.0.1")));
+
+    // no allow list
+    let policy = AccessPolicy::new(&AccessConfig {
+      deny: vec!["1.1.1.1".to_owned()],
+      ..Default::default()
+    })
+    .unwrap();
+    assert!(policy.check(ip("8.8.8.8")));
+    assert!(!policy.check(ip("1.1.1.1")));
+
+    std::fs::write(&file, "block 1.1.1.1").unwrap();
+    assert!(AccessPolicy::new(&config).is_err());
+    std::fs::remove_file(&file).ok();
+
+    // not changed if it can not be saved
+    let policy = AccessPolicy::new(&AccessConfig {
+      file: format!("{}/access.txt", file),
+      ..Default::default()
+    })
+    .unwrap();
+    assert!(policy
+      .update(true, "1.1.1.1".parse().unwrap(), false)
+      .is_err());
+    assert!(policy.check(ip("1.1.1.1")));
+    assert!(policy.list().is_empty());
+  }
+}
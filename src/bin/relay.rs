@@ -0,0 +1,23 @@
+use clap::Parser;
+use nimbus_common::{config::RELAY_PORT, ResultType};
+
+use nimbuslink_server::relay_server::RelayServer;
+
+/// Command line arguments of the relay server.
+#[derive(Debug, Parser)]
+#[command(version, about = "NimbusLink relay server")]
+struct Args {
+  /// relay tcp port
+  #[arg(short, long, env = "NIMBUS_RELAY_PORT", default_value_t = RELAY_PORT)]
+  port: i32,
+  /// log level, e.g. `info` or `debug`
+  #[arg(short, long, env = "NIMBUS_LOG_LEVEL", default_value = "debug")]
+  log_level: String,
+}
+
+fn main() -> ResultType<()> {
+  let args = Args::parse();
+  nimbus_common::common::logger_initialize::logger_init!(&args.log_level);
+  RelayServer::start(args.port)?;
+  Ok(())
+}
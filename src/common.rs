@@ -1,4 +1,7 @@
-use std::time::Instant;
+use std::{
+  net::IpAddr,
+  time::{Instant, SystemTime, UNIX_EPOCH},
+};
 
 pub(crate) fn get_expired_time() -> Instant {
   let now = Instant::now();
@@ -6,3 +9,20 @@ pub(crate) fn get_expired_time() -> Instant {
     .checked_sub(std::time::Duration::from_secs(3600))
     .unwrap_or(now)
 }
+
+/// The ip as a number, ipv4 as ipv4-mapped ipv6, to compare the ranges of
+/// both.
+pub(crate) fn ip_to_u128(ip: IpAddr) -> u128 {
+  match ip.to_canonical() {
+    IpAddr::V4(ip) => u128::from(ip.to_ipv6_mapped()),
+    IpAddr::V6(ip) => u128::from(ip),
+  }
+}
+
+/// Seconds since the unix epoch.
+pub(crate) fn get_unix_time() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|x| x.as_secs())
+    .unwrap_or_default()
+}
@@ -0,0 +1,466 @@
+use std::{collections::HashMap, net::IpAddr, path::PathBuf};
+
+use clap::Parser;
+use nimbus_common::{anyhow::Context, config::REG_INTERVAL, ResultType};
+use serde_derive::Deserialize;
+
+/// Command line arguments of the rendezvous server.
+///
+/// Every argument can also be given by its environment variable, and
+/// overrides the value of the config file.
+#[derive(Debug, Default, Parser)]
+#[command(version, about = "NimbusLink rendezvous server")]
+pub struct Args {
+  /// TOML config file
+  #[arg(short, long, env = "NIMBUS_CONFIG")]
+  pub config: Option<PathBuf>,
+  /// main tcp/udp port
+  #[arg(short, long, env = "NIMBUS_PORT")]
+  pub port: Option<i32>,
+  /// tcp port for the NAT test, default `port - 1`
+  #[arg(long, env = "NIMBUS_NAT_PORT")]
+  pub nat_port: Option<i32>,
+  /// websocket port, default `port + 2`
+  #[arg(long, env = "NIMBUS_WS_PORT")]
+  pub ws_port: Option<i32>,
+  /// address of all the listeners, default any address of ipv6 and ipv4
+  #[arg(short, long, env = "NIMBUS_BIND_ADDR")]
+  pub bind_addr: Option<IpAddr>,
+  /// relay servers, separated by `,`
+  #[arg(short, long, env = "NIMBUS_RELAY_SERVERS", value_delimiter = ',')]
+  pub relay_servers: Option<Vec<String>>,
+  /// rendezvous servers pushed to the clients, separated by `,`
+  #[arg(long, env = "NIMBUS_RENDEZVOUS_SERVERS", value_delimiter = ',')]
+  pub rendezvous_servers: Option<Vec<String>>,
+  /// receive buffer size of the udp socket, 0 for the system default
+  #[arg(short = 'M', long, env = "NIMBUS_UDP_RECV_BUFFER_SIZE")]
+  pub udp_recv_buffer_size: Option<usize>,
+  /// log level, e.g. `info` or `debug,sqlx=warn`
+  #[arg(short, long, env = "NIMBUS_LOG_LEVEL")]
+  pub log_level: Option<String>,
+  /// peer store: `memory`, `json://<path>` or `sqlite://<path>`, `DB_URL`
+  /// is read if `NIMBUS_DB_URL` is not set
+  #[arg(long, env = "NIMBUS_DB_URL")]
+  pub db_url: Option<String>,
+  /// key the clients have to present, `-` for the server's public key
+  #[arg(short, long, env = "NIMBUS_KEY")]
+  pub key: Option<String>,
+  /// file of the server's key pair, the public key is in `<file>.pub`
+  #[arg(long, env = "NIMBUS_KEY_FILE")]
+  pub key_file: Option<String>,
+  /// tell all the peers to connect through a relay server, `true` or `false`
+  #[arg(long, env = "NIMBUS_ALWAYS_USE_RELAY")]
+  pub always_use_relay: Option<bool>,
+}
+
+/// A token bucket of `burst` requests, refilled at `rate` requests per
+/// second, `rate = 0` for no limit.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct BucketConfig {
+  pub rate: f64,
+  pub burst: f64,
+}
+
+/// Rate limits of the requests and thresholds of the registrations.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct LimitsConfig {
+  /// every message of an ip, on udp, tcp and websocket, but the
+  /// `RegisterPeer` heartbeats
+  pub ip: BucketConfig,
+  /// every message of a subnet, but the heartbeats
+  pub subnet: BucketConfig,
+  /// `RegisterPeer` heartbeats of an id, sent every `REG_INTERVAL`
+  pub heartbeat: BucketConfig,
+  /// `RegisterPeer` heartbeats of an ip, of all the clients behind it
+  pub heartbeat_ip: BucketConfig,
+  /// prefix length of the ipv4 subnets
+  pub subnet_prefix_v4: u32,
+  /// prefix length of the ipv6 subnets
+  pub subnet_prefix_v6: u32,
+  /// `RegisterPk` of an ip
+  pub register_ip: BucketConfig,
+  /// `RegisterPk` of an id
+  pub register_id: BucketConfig,
+  /// max distinct ids registered from an ip within `ip_ids_duration`,
+  /// 0 for no limit, e.g. for the clients behind a carrier-grade NAT
+  pub ip_ids_max: usize,
+  /// seconds
+  pub ip_ids_duration: u64,
+  /// ip changes of a peer are tracked within this duration (seconds)
+  pub ip_change_duration: u64,
+  /// a peer is flagged when it moves to more distinct ips within
+  /// `ip_change_duration`, e.g. a cloned device or a hijacked id, 0 to
+  /// never flag
+  pub ip_change_max: usize,
+  /// reject the `RegisterPk` of a flagged peer with `TOO_FREQUENT`
+  pub ip_change_reject: bool,
+  /// a flagged peer has to register with its uuid and public key, a new
+  /// key is only accepted otherwise
+  pub ip_change_strict: bool,
+}
+
+impl Default for LimitsConfig {
+  fn default() -> Self {
+    LimitsConfig {
+      ip: BucketConfig {
+        rate: 20.,
+        burst: 200.,
+      },
+      subnet: BucketConfig {
+        rate: 200.,
+        burst: 2000.,
+      },
+      // twice the heartbeats of a client
+      heartbeat: BucketConfig {
+        rate: 2. * 1000. / REG_INTERVAL as f64,
+        burst: 6.,
+      },
+      // a thousand clients behind a NAT
+      heartbeat_ip: BucketConfig {
+        rate: 1000. * 1000. / REG_INTERVAL as f64,
+        burst: 2000.,
+      },
+      subnet_prefix_v4: 24,
+      subnet_prefix_v6: 64,
+      register_ip: BucketConfig {
+        rate: 0.5,
+        burst: 30.,
+      },
+      register_id: BucketConfig {
+        rate: 0.5,
+        burst: 3.,
+      },
+      ip_ids_max: 300,
+      ip_ids_duration: 3600 * 24,
+      ip_change_duration: 180,
+      ip_change_max: 10,
+      ip_change_reject: false,
+      ip_change_strict: false,
+    }
+  }
+}
+
+/// Format of the peer ids accepted by `RegisterPk`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct IdPolicyConfig {
+  /// min length in chars
+  pub min_len: usize,
+  /// max length in chars
+  pub max_len: usize,
+  /// regex the whole id has to match, empty for any
+  pub pattern: String,
+  /// ids nobody can register
+  pub reserved: Vec<String>,
+}
+
+impl Default for IdPolicyConfig {
+  fn default() -> Self {
+    IdPolicyConfig {
+      min_len: 6,
+      max_len: 100,
+      pattern: r"^[\w-]+$".to_owned(),
+      reserved: vec!["(:test_nimbus:)".to_owned()],
+    }
+  }
+}
+
+/// Eviction of the expired entries from the in-memory maps.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct JanitorConfig {
+  /// seconds between the sweeps, applied on start
+  pub interval: u64,
+  /// an ip is forgotten by the ip blocker if it has not registered an id
+  /// within this time (seconds)
+  pub ip_blocker_ttl: u64,
+  /// the ip changes of a peer are forgotten after this time (seconds)
+  pub ip_changes_ttl: u64,
+  /// a peer is unloaded from memory if it has not registered within this
+  /// time (seconds), only with a persistent peer store, 0 to keep all
+  pub peer_ttl: u64,
+}
+
+impl Default for JanitorConfig {
+  fn default() -> Self {
+    JanitorConfig {
+      interval: 60,
+      ip_blocker_ttl: 3600 * 24,
+      ip_changes_ttl: 3600,
+      peer_ttl: 3600 * 24 * 7,
+    }
+  }
+}
+
+/// Networks which can reach the server, `10.0.0.0/8`, `2001:db8::/32` or a
+/// single ip.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct AccessConfig {
+  /// only these networks if not empty
+  pub allow: Vec<String>,
+  /// never these networks, even if allowed
+  pub deny: Vec<String>,
+  /// the networks added by the admin console, empty to not save them
+  pub file: String,
+}
+
+impl Default for AccessConfig {
+  fn default() -> Self {
+    AccessConfig {
+      allow: vec![],
+      deny: vec![],
+      file: "nimbus_access.txt".to_owned(),
+    }
+  }
+}
+
+/// A geographic location, of an ip range in the geo database or of a relay
+/// server.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Location {
+  pub region: String,
+  pub lat: f64,
+  pub lon: f64,
+}
+
+/// Configuration of the rendezvous server, from the config file,
+/// the environment variables and the command line arguments.
+///
+/// ```toml
+/// port = 8080
+/// relay_servers = ["relay.example.com:8081"]
+/// log_level = "info"
+///
+/// geo_db = "geoip.csv"
+///
+/// [relay_regions]
+/// "relay.example.com:8081" = { region = "eu", lat = 50.11, lon = 8.68 }
+///
+/// [access]
+/// deny = ["203.0.113.0/24"]
+///
+/// [limits]
+/// ip_ids_max = 1000
+/// register_ip = { rate = 1.0, burst = 60.0 }
+/// ip_change_reject = true
+///
+/// [id_policy]
+/// min_len = 8
+/// pattern = "^[0-9]+$"
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+  pub port: i32,
+  /// 0 for `port - 1`
+  pub nat_port: i32,
+  /// 0 for `port + 2`
+  pub ws_port: i32,
+  pub bind_addr: Option<IpAddr>,
+  pub relay_servers: Vec<String>,
+  pub rendezvous_servers: Vec<String>,
+  pub udp_recv_buffer_size: usize,
+  pub log_level: String,
+  pub db_url: String,
+  /// empty for any client, `-` for the server's public key
+  pub key: String,
+  pub key_file: String,
+  /// a peer is reported online by `OnlineResponse` if it has registered
+  /// within this time (ms)
+  pub online_timeout: u64,
+  /// the peers connect through a relay server instead of hole punching,
+  /// unless a peer has its own setting
+  pub always_use_relay: bool,
+  /// CSV file of `start_ip,end_ip,region,lat,lon`, empty to hand out the
+  /// relay servers in turn
+  pub geo_db: String,
+  /// locations of the relay servers, the closest one to the peers is
+  /// chosen if they are located by `geo_db`
+  pub relay_regions: HashMap<String, Location>,
+  pub limits: LimitsConfig,
+  pub janitor: JanitorConfig,
+  pub access: AccessConfig,
+  pub id_policy: IdPolicyConfig,
+}
+
+impl Default for ServerConfig {
+  fn default() -> Self {
+    ServerConfig {
+      port: 8080,
+      nat_port: 0,
+      ws_port: 0,
+      bind_addr: None,
+      relay_servers: vec![],
+      rendezvous_servers: vec![],
+      udp_recv_buffer_size: 0,
+      log_level: "debug".to_owned(),
+      db_url: "sqlite://nimbus.sqlite3".to_owned(),
+      key: "".to_owned(),
+      key_file: "id_ed25519".to_owned(),
+      online_timeout: 30_000,
+      always_use_relay: false,
+      geo_db: "".to_owned(),
+      relay_regions: Default::default(),
+      limits: Default::default(),
+      janitor: Default::default(),
+      access: Default::default(),
+      id_policy: Default::default(),
+    }
+  }
+}
+
+impl ServerConfig {
+  /// Load the config file if any, then apply the arguments on it.
+  pub fn load(args: &Args) -> ResultType<ServerConfig> {
+    let mut config = match &args.config {
+      Some(path) => {
+        let content = std::fs::read_to_string(path)
+          .with_context(|| format!("failed to read {}", path.display()))?;
+        toml::from_str(&content)
+          .with_context(|| format!("failed to parse {}", path.display()))?
+      }
+      None => ServerConfig::default(),
+    };
+    config.apply(args);
+    Ok(config)
+  }
+
+  fn apply(&mut self, args: &Args) {
+    if let Some(x) = args.port {
+      self.port = x;
+    }
+    if let Some(x) = args.nat_port {
+      self.nat_port = x;
+    }
+    if let Some(x) = args.ws_port {
+      self.ws_port = x;
+    }
+    if let Some(x) = args.bind_addr {
+      self.bind_addr = Some(x);
+    }
+    if let Some(x) = &args.relay_servers {
+      self.relay_servers = x.clone();
+    }
+    if let Some(x) = &args.rendezvous_servers {
+      self.rendezvous_servers = x.clone();
+    }
+    if let Some(x) = args.udp_recv_buffer_size {
+      self.udp_recv_buffer_size = x;
+    }
+    if let Some(x) = &args.log_level {
+      self.log_level = x.clone();
+    }
+    if let Some(x) =
+      args.db_url.clone().or_else(|| std::env::var("DB_URL").ok())
+    {
+      self.db_url = x;
+    }
+    if let Some(x) = &args.key {
+      self.key = x.clone();
+    }
+    if let Some(x) = &args.key_file {
+      self.key_file = x.clone();
+    }
+    if let Some(x) = args.always_use_relay {
+      self.always_use_relay = x;
+    }
+  }
+
+  /// Whether the settings only applied on start are the same, the others
+  /// are reloaded on SIGHUP.
+  pub fn same_listeners(&self, other: &ServerConfig) -> bool {
+    self.port == other.port
+      && self.nat_port() == other.nat_port()
+      && self.ws_port() == other.ws_port()
+      && self.bind_addr == other.bind_addr
+      && self.udp_recv_buffer_size == other.udp_recv_buffer_size
+      && self.db_url == other.db_url
+      && self.key == other.key
+      && self.key_file == other.key_file
+  }
+
+  pub fn nat_port(&self) -> i32 {
+    if self.nat_port > 0 {
+      self.nat_port
+    } else {
+      self.port - 1
+    }
+  }
+
+  pub fn ws_port(&self) -> i32 {
+    if self.ws_port > 0 {
+      self.ws_port
+    } else {
+      self.port + 2
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_load() {
+    let config: ServerConfig = toml::from_str(
+      r#"
+      port = 9000
+      ws_port = 9100
+      relay_servers = ["a:8081", "b:8081"]
+      [relay_regions]
+      "a:8081" = { region = "eu", lat = 50.11, lon = 8.68 }
+      [limits]
+      ip_ids_max = 1000
+      ip = { rate = 5, burst = 50 }
+      ip_change_reject = true
+      "#,
+    )
+    .unwrap();
+    assert_eq!(config.port, 9000);
+    assert_eq!(config.nat_port(), 8999);
+    assert_eq!(config.ws_port(), 9100);
+    assert_eq!(config.relay_servers, vec!["a:8081", "b:8081"]);
+    assert_eq!(config.relay_regions["a:8081"].region, "eu");
+    assert_eq!(config.limits.ip_ids_max, 1000);
+    assert_eq!(config.limits.ip.rate, 5.);
+    assert_eq!(config.limits.register_ip.burst, 30.);
+    assert!(config.limits.ip_change_reject);
+    assert_eq!(config.limits.ip_change_max, 10);
+    assert_eq!(config.log_level, "debug");
+
+    // the arguments override the config file
+    let mut config = config;
+    config.apply(&Args::parse_from([
+      "nimbuslink_server",
+      "-p",
+      "7000",
+      "-r",
+      "c:8081,d:8081",
+      "--always-use-relay",
+      "true",
+    ]));
+    assert_eq!(config.port, 7000);
+    assert!(config.always_use_relay);
+    assert_eq!(config.ws_port(), 9100);
+    assert_eq!(config.relay_servers, vec!["c:8081", "d:8081"]);
+  }
+
+  #[test]
+  fn test_same_listeners() {
+    let config = ServerConfig::default();
+    let mut other = ServerConfig {
+      relay_servers: vec!["a:8081".to_owned()],
+      log_level: "info".to_owned(),
+      ..Default::default()
+    };
+    other.limits.ip_ids_max = 1;
+    assert!(config.same_listeners(&other));
+    // an explicit default port is the same port
+    other.ws_port = 8082;
+    assert!(config.same_listeners(&other));
+    other.nat_port = 9000;
+    assert!(!config.same_listeners(&other));
+  }
+}
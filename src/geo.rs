@@ -0,0 +1,217 @@
+use std::{collections::HashMap, net::IpAddr};
+
+use nimbus_common::{
+  anyhow::{anyhow, Context},
+  logger::*,
+  ResultType,
+};
+
+use crate::{
+  common::ip_to_u128,
+  config::{Location, ServerConfig},
+};
+
+/// Ip ranges with their locations, from a CSV file of
+/// `start_ip,end_ip,region,lat,lon` lines.
+///
+/// ```text
+/// # comment
+/// 1.0.0.0,1.0.0.255,oc,-33.49,143.21
+/// 2a02:2000::,2a02:2fff:ffff:ffff:ffff:ffff:ffff:ffff,eu,50.11,8.68
+/// ```
+#[derive(Debug, Default)]
+pub struct GeoDb {
+  /// sorted by the start, ipv4 as ipv4-mapped ipv6
+  ranges: Vec<(u128, u128, Location)>,
+}
+
+impl GeoDb {
+  pub fn load(path: &str) -> ResultType<GeoDb> {
+    let content = std::fs::read_to_string(path)
+      .with_context(|| format!("failed to read {}", path))?;
+    GeoDb::parse(&content).with_context(|| format!("invalid {}", path))
+  }
+
+  pub fn parse(content: &str) -> ResultType<GeoDb> {
+    let mut ranges = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let range = parse_line(line)
+        .with_context(|| format!("line {}: {}", i + 1, line))?;
+      ranges.push(range);
+    }
+    ranges.sort_by_key(|x| x.0);
+    Ok(GeoDb { ranges })
+  }
+
+  pub fn len(&self) -> usize {
+    self.ranges.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.ranges.is_empty()
+  }
+
+  pub fn lookup(&self, ip: IpAddr) -> Option<&Location> {
+    let ip = ip_to_u128(ip);
+    // the last range starting at or before the ip
+    let i = self.ranges.partition_point(|x| x.0 <= ip);
+    let (_, end, location) = self.ranges.get(i.checked_sub(1)?)?;
+    (ip <= *end).then_some(location)
+  }
+}
+
+fn parse_line(line: &str) -> ResultType<(u128, u128, Location)> {
+  let fds: Vec<&str> = line.split(',').map(|x| x.trim()).collect();
+  let [start, end, region, lat, lon] = fds[..] else {
+    return Err(anyhow!("expected start_ip,end_ip,region,lat,lon"));
+  };
+  let start = ip_to_u128(start.parse()?);
+  let end = ip_to_u128(end.parse()?);
+  if start > end {
+    return Err(anyhow!("start after end"));
+  }
+  let location = Location {
+    region: region.to_owned(),
+    lat: lat.parse()?,
+    lon: lon.parse()?,
+  };
+  Ok((start, end, location))
+}
+
+/// Great-circle distance in km.
+fn distance(a: &Location, b: &Location) -> f64 {
+  let (lat1, lat2) = (a.lat.to_radians(), b.lat.to_radians());
+  let dlat = lat2 - lat1;
+  let dlon = (b.lon - a.lon).to_radians();
+  let h = (dlat / 2.).sin().powi(2)
+    + lat1.cos() * lat2.cos() * (dlon / 2.).sin().powi(2);
+  2. * 6371. * h.sqrt().asin()
+}
+
+/// The geo database and the locations of the relay servers.
+#[derive(Debug, Default)]
+pub struct Geo {
+  db: Option<GeoDb>,
+  relays: HashMap<String, Location>,
+}
+
+impl Geo {
+  /// Load `geo_db` if any, the relays are tagged by `relay_regions`.
+  pub fn new(config: &ServerConfig) -> ResultType<Geo> {
+    let db = if config.geo_db.is_empty() {
+      None
+    } else {
+      let db = GeoDb::load(&config.geo_db)?;
+      info!("{} ip ranges loaded from {}", db.len(), config.geo_db);
+      Some(db)
+    };
+    Ok(Geo {
+      db,
+      relays: config.relay_regions.clone(),
+    })
+  }
+
+  pub fn lookup(&self, ip: IpAddr) -> Option<&Location> {
+    self.db.as_ref()?.lookup(ip)
+  }
+
+  /// The relay server with the least total distance to the located peers,
+  /// among the tagged ones in `relay_servers`.
+  ///
+  /// `None` if no peer is located or no relay is tagged.
+  pub fn closest_relay<'a>(
+    &self,
+    relay_servers: &'a [String],
+    ips: &[IpAddr],
+  ) -> Option<&'a String> {
+    let peers: Vec<&Location> =
+      ips.iter().filter_map(|ip| self.lookup(*ip)).collect();
+    if peers.is_empty() {
+      return None;
+    }
+    relay_servers
+      .iter()
+      .filter_map(|relay_server| {
+        let relay = self.relays.get(relay_server)?;
+        let d: f64 = peers.iter().map(|peer| distance(peer, relay)).sum();
+        Some((relay_server, d))
+      })
+      .min_by(|a, b| a.1.total_cmp(&b.1))
+      .map(|(relay_server, _)| relay_server)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  static CSV: &str = "
+    # start,end,region,lat,lon
+    8.8.8.0,8.8.8.255,us,37.75,-97.82
+    2.16.0.0,2.16.255.255,eu,50.11,8.68
+    2a02:2000::,2a02:2fff:ffff:ffff:ffff:ffff:ffff:ffff,eu,52.52,13.40
+  ";
+
+  #[test]
+  fn test_lookup() {
+    let db = GeoDb::parse(CSV).unwrap();
+    assert_eq!(db.len(), 3);
+    let region = |ip: &str| db.lookup(ip.parse().unwrap()).map(|x| &x.region);
+    assert_eq!(region("8.8.8.8").unwrap(), "us");
+    assert_eq!(region("::ffff:2.16.1.1").unwrap(), "eu");
+    assert_eq!(region("2a02:2001::1").unwrap(), "eu");
+    assert!(region("8.8.9.1").is_none());
+    assert!(region("1.1.1.1").is_none());
+    assert!(region("::1").is_none());
+
+    assert!(GeoDb::parse("1.1.1.1,1.1.1.0,x,0,0").is_err());
+    assert!(GeoDb::parse("1.1.1.1,1.1.1.2,x,0").is_err());
+  }
+
+  #[test]
+  fn test_closest_relay() {
+    let mut relays = HashMap::new();
+    relays.insert(
+      "us:8081".to_owned(),
+      Location {
+        region: "us".to_owned(),
+        lat: 39.04,
+        lon: -77.49,
+      },
+    );
+    relays.insert(
+      "eu:8081".to_owned(),
+      Location {
+        region: "eu".to_owned(),
+        lat: 50.11,
+        lon: 8.68,
+      },
+    );
+    let geo = Geo {
+      db: Some(GeoDb::parse(CSV).unwrap()),
+      relays,
+    };
+    let relay_servers = vec![
+      "us:8081".to_owned(),
+      "eu:8081".to_owned(),
+      "other:8081".to_owned(),
+    ];
+    let closest = |ips: &[&str]| {
+      let ips: Vec<IpAddr> = ips.iter().map(|x| x.parse().unwrap()).collect();
+      geo.closest_relay(&relay_servers, &ips).cloned()
+    };
+    assert_eq!(closest(&["2.16.0.1", "2a02:2000::1"]).unwrap(), "eu:8081");
+    assert_eq!(closest(&["8.8.8.8"]).unwrap(), "us:8081");
+    // an unknown peer does not count
+    assert_eq!(closest(&["1.1.1.1", "2.16.0.1"]).unwrap(), "eu:8081");
+    assert!(closest(&["1.1.1.1"]).is_none());
+    // only the given relays
+    assert!(geo
+      .closest_relay(&relay_servers[2..], &["8.8.8.8".parse().unwrap()])
+      .is_none());
+  }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+
+use nimbus_common::{anyhow::Context, regex::Regex, ResultType};
+
+use crate::config::IdPolicyConfig;
+
+/// The compiled `IdPolicyConfig`.
+#[derive(Debug)]
+pub struct IdPolicy {
+  min_len: usize,
+  max_len: usize,
+  regex: Option<Regex>,
+  reserved: HashSet<String>,
+}
+
+impl IdPolicy {
+  pub fn new(config: &IdPolicyConfig) -> ResultType<IdPolicy> {
+    let regex = if config.pattern.is_empty() {
+      None
+    } else {
+      Some(
+        Regex::new(&config.pattern)
+          .with_context(|| format!("invalid id pattern {}", config.pattern))?,
+      )
+    };
+    Ok(IdPolicy {
+      min_len: config.min_len,
+      max_len: config.max_len,
+      regex,
+      reserved: config.reserved.iter().cloned().collect(),
+    })
+  }
+
+  /// Whether a peer can register the id.
+  pub fn check(&self, id: &str) -> bool {
+    let len = id.chars().count();
+    if len < self.min_len || len > self.max_len {
+      return false;
+    }
+    if self.reserved.contains(id) {
+      return false;
+    }
+    match &self.regex {
+      Some(regex) => regex.is_match(id),
+      None => true,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_default_policy() {
+    let policy = IdPolicy::new(&Default::default()).unwrap();
+    assert!(policy.check("123456"));
+    assert!(policy.check("my-desk_01"));
+    assert!(!policy.check("12345"));
+    assert!(!policy.check(&"1".repeat(101)));
+    assert!(!policy.check("123 456"));
+    assert!(!policy.check("(:test_nimbus:)"));
+  }
+
+  #[test]
+  fn test_custom_policy() {
+    let policy = IdPolicy::new(&IdPolicyConfig {
+      min_len: 3,
+      max_len: 9,
+      pattern: "^[0-9]+$".to_owned(),
+      reserved: vec!["000000".to_owned()],
+    })
+    .unwrap();
+    assert!(policy.check("123"));
+    assert!(!policy.check("abc"));
+    assert!(!policy.check("1234567890"));
+    assert!(!policy.check("000000"));
+
+    let policy = IdPolicy::new(&IdPolicyConfig {
+      pattern: "".to_owned(),
+      ..Default::default()
+    })
+    .unwrap();
+    assert!(policy.check("123 456"));
+
+    assert!(IdPolicy::new(&IdPolicyConfig {
+      pattern: "[".to_owned(),
+      ..Default::default()
+    })
+    .is_err());
+  }
+}
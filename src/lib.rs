@@ -1,3 +1,11 @@
+pub mod access_policy;
 pub mod common;
+pub mod config;
+pub mod geo;
+pub mod id_policy;
 pub mod peer;
+pub mod peer_store;
+pub mod rate_limiter;
+pub mod relay_server;
 pub mod rendezvous_server;
+pub mod server_key;
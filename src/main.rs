@@ -1,10 +1,18 @@
+use clap::Parser;
 use nimbus_common::ResultType;
 
-use nimbuslink_server::rendezvous_server::RendezvousServer;
+use nimbuslink_server::{
+  config::{Args, ServerConfig},
+  rendezvous_server::RendezvousServer,
+};
 
 fn main() -> ResultType<()> {
-  nimbus_common::common::logger_initialize::logger_init!();
-  let port = 8080;
-  RendezvousServer::start(port, 0)?;
+  let args = Args::parse();
+  let config = ServerConfig::load(&args)?;
+  nimbus_common::common::logger_initialize::logger_init!(
+    logger,
+    &config.log_level
+  );
+  RendezvousServer::start(args, config, logger)?;
   Ok(())
 }
@@ -2,7 +2,7 @@ use std::{
   collections::{HashMap, HashSet},
   net::SocketAddr,
   sync::Arc,
-  time::Instant,
+  time::{Duration, Instant},
 };
 
 use nimbus_common::{
@@ -15,7 +15,10 @@ use nimbus_common::{
 };
 use serde_derive::{Deserialize, Serialize};
 
-use crate::common::get_expired_time;
+use crate::{
+  common::{get_expired_time, get_unix_time},
+  peer_store::PeerStore,
+};
 
 /// IP blocking information
 ///
@@ -24,27 +27,46 @@ use crate::common::get_expired_time;
 /// 2. Content Filtering
 /// 3. DDos Mitigation
 /// 4. User Management
-/// [`check_ip_blocker`]
-type IpBlockMap = HashMap<String, ((u32, Instant), (HashSet<String>, Instant))>;
-/// User status information
-type UserStatusMap = HashMap<Vec<u8>, Arc<(Option<Vec<u8>>, bool)>>;
-/// Ip change information
-type IpChangesMap = HashMap<String, (Instant, HashMap<String, i32>)>;
+///
+/// [`check_ip_blocker`], the distinct ids registered from an ip, the request
+/// rates are limited by `Limiter`
+type IpBlockMap = HashMap<String, (HashSet<String>, Instant)>;
+/// The devices disabled by the admin, by guid: (id, disabled until),
+/// loaded from the store on start
+type UserStatusMap = HashMap<Vec<u8>, (String, u64)>;
+/// The ips a peer has moved to since a time, with the number of moves
+pub(crate) type IpChangesMap = HashMap<String, (Instant, HashMap<String, i32>)>;
 pub(crate) static IP_BLOCKER: Lazy<Mutex<IpBlockMap>> =
   Lazy::new(Default::default);
 pub(crate) static USER_STATUS: Lazy<RwLock<UserStatusMap>> =
   Lazy::new(Default::default);
 pub(crate) static IP_CHANGES: Lazy<Mutex<IpChangesMap>> =
   Lazy::new(Default::default);
-pub static IP_CHANGE_DUR: u64 = 180;
-pub static IP_CHANGE_DUR_X2: u64 = IP_CHANGE_DUR * 2;
-pub static DAY_SECONDS: u64 = 3600 * 24;
-pub static IP_BLOCK_DUR: u64 = 60;
+/// a peer is considered offline if it has not registered within this time (ms)
+pub static REG_TIMEOUT: i32 = 30_000;
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub(crate) struct PeerInfo {
   #[serde(default)]
   pub(crate) ip: String,
+  /// connect through a relay server instead of hole punching, `None` to
+  /// follow the server setting
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) always_use_relay: Option<bool>,
+  /// disabled by the admin until this unix time (s), `u64::MAX` for good,
+  /// 0 if enabled
+  #[serde(default, skip_serializing_if = "is_zero")]
+  pub(crate) disabled_until: u64,
+}
+
+impl PeerInfo {
+  pub(crate) fn is_disabled(&self) -> bool {
+    self.disabled_until > get_unix_time()
+  }
+}
+
+fn is_zero(x: &u64) -> bool {
+  *x == 0
 }
 
 pub(crate) struct Peer {
@@ -54,7 +76,6 @@ pub(crate) struct Peer {
   pub(crate) uuid: Bytes,
   pub(crate) pk: Bytes,
   pub(crate) peer_info: PeerInfo,
-  pub(crate) reg_pk: (u32, Instant),
 }
 
 pub(crate) type LockPeer = Arc<RwLock<Peer>>;
@@ -62,28 +83,42 @@ pub(crate) type LockPeer = Arc<RwLock<Peer>>;
 impl Default for Peer {
   fn default() -> Self {
     Peer {
-      socket_addr: "0.0.0.0".parse().unwrap(),
+      socket_addr: "0.0.0.0:0".parse().unwrap(),
       last_register_time: get_expired_time(),
       guid: Vec::new(),
       uuid: Bytes::new(),
       pk: Bytes::new(),
       peer_info: Default::default(),
-      reg_pk: (0, get_expired_time()),
     }
   }
 }
 
+/// Registered peers, the in-memory map is a write-through cache of the
+/// peer store.
 #[derive(Clone)]
 pub(crate) struct PeerMap {
   map: Arc<RwLock<HashMap<String, LockPeer>>>,
-  // TODO: sqlx support
+  pub(crate) store: Arc<dyn PeerStore>,
 }
 
 impl PeerMap {
-  pub(crate) async fn new() -> ResultType<Self> {
+  pub(crate) async fn new(store: Box<dyn PeerStore>) -> ResultType<Self> {
     let pm = PeerMap {
       map: Default::default(),
+      store: store.into(),
     };
+    let mut user_status = USER_STATUS.write().await;
+    for record in pm.store.list_disabled_peers().await? {
+      let info =
+        serde_json::from_str::<PeerInfo>(&record.info).unwrap_or_default();
+      if info.disabled_until > 0 {
+        user_status.insert(record.guid, (record.id, info.disabled_until));
+      }
+    }
+    if !user_status.is_empty() {
+      info!("{} disabled devices", user_status.len());
+    }
+    drop(user_status);
     Ok(pm)
   }
 
@@ -104,7 +139,7 @@ impl PeerMap {
       w.socket_addr = addr;
       w.uuid = uuid.clone();
       w.pk = pk.clone();
-      w.last_register_time = get_expired_time();
+      w.last_register_time = Instant::now();
       w.peer_info.ip = ip;
 
       (
@@ -114,48 +149,499 @@ impl PeerMap {
     };
 
     if guid.is_empty() {
-      // TODO: insert record to sqlx
-      peer.write().await.guid = guid;
+      match self.store.insert_peer(&id, &uuid, &pk, &info_str).await {
+        Err(err) => {
+          error!("store.insert_peer failed: {}", err);
+          return register_pk_response::Result::SERVER_ERROR;
+        }
+        Ok(guid) => {
+          peer.write().await.guid = guid;
+        }
+      }
     } else {
-      // TODO: update the record in sqlx
+      if let Err(err) = self.store.update_pk(&guid, &id, &pk, &info_str).await {
+        error!("store.update_pk failed: {}", err);
+        return register_pk_response::Result::SERVER_ERROR;
+      }
       info!("pk updated instead of insert");
     }
 
     register_pk_response::Result::OK
   }
 
+  /// The peer of the id, loaded from the store if not in memory, a store
+  /// error is logged as not found.
   #[inline]
   pub(crate) async fn get(&self, id: &str) -> Option<LockPeer> {
+    match self.try_get(id).await {
+      Ok(p) => p,
+      Err(err) => {
+        error!("store.get_peer {} failed: {}", id, err);
+        None
+      }
+    }
+  }
+
+  async fn try_get(&self, id: &str) -> ResultType<Option<LockPeer>> {
     let p = self.map.read().await.get(id).cloned();
     if p.is_some() {
-      return p;
-    } else {
-      // TODO: get peer from sqlx
+      return Ok(p);
+    } else if let Some(v) = self.store.get_peer(id).await? {
+      let peer = Peer {
+        guid: v.guid,
+        uuid: v.uuid.into(),
+        pk: v.pk.into(),
+        peer_info: serde_json::from_str::<PeerInfo>(&v.info)
+          .unwrap_or_default(),
+        ..Default::default()
+      };
+      // another task may have loaded or created it meanwhile
+      let peer = self
+        .map
+        .write()
+        .await
+        .entry(id.to_owned())
+        .or_insert_with(|| Arc::new(RwLock::new(peer)))
+        .clone();
+      return Ok(Some(peer));
     }
-    None
+    Ok(None)
   }
 
+  /// The peer of the id, a new unregistered one if unknown.
   #[inline]
-  pub(crate) async fn get_or(&self, id: &str) -> LockPeer {
-    if let Some(p) = self.get(id).await {
-      return p;
+  pub(crate) async fn get_or(&self, id: &str) -> ResultType<LockPeer> {
+    if let Some(p) = self.try_get(id).await? {
+      return Ok(p);
     }
     let mut w = self.map.write().await;
     if let Some(p) = w.get(id) {
-      return p.clone();
+      return Ok(p.clone());
     }
     let tmp = LockPeer::default();
     w.insert(id.to_owned(), tmp.clone());
-    tmp
+    Ok(tmp)
+  }
+
+  /// Rename the peer of `old_id` to `id`, the requester has to own it
+  /// with the same uuid and public key.
+  ///
+  /// The new id is taken in the map before the record is moved in the
+  /// store, and given back if the store fails. The ip changes of the old
+  /// id are moved to the new one.
+  pub(crate) async fn change_id(
+    &self,
+    old_id: &str,
+    id: &str,
+    uuid: &[u8],
+    pk: &[u8],
+  ) -> register_pk_response::Result {
+    let Some(peer) = self.get(old_id).await else {
+      return register_pk_response::Result::UUID_MISMATCH;
+    };
+    {
+      let r = peer.read().await;
+      if r.uuid.is_empty() || r.uuid != uuid || r.pk != pk {
+        warn!("change id {} -> {}: uuid/pk mismatch", old_id, id);
+        return register_pk_response::Result::UUID_MISMATCH;
+      }
+    }
+    // load the new id from the store if it exists
+    match self.try_get(id).await {
+      Ok(Some(_)) => return register_pk_response::Result::ID_EXISTS,
+      Ok(None) => {}
+      Err(err) => {
+        error!("store.get_peer {} failed: {}", id, err);
+        return register_pk_response::Result::SERVER_ERROR;
+      }
+    }
+
+    {
+      let mut map = self.map.write().await;
+      if map.contains_key(id) {
+        return register_pk_response::Result::ID_EXISTS;
+      }
+      map.insert(id.to_owned(), peer.clone());
+    }
+    let res = {
+      let r = peer.read().await;
+      if r.guid.is_empty() {
+        Ok(())
+      } else {
+        let info_str = serde_json::to_string(&r.peer_info).unwrap_or_default();
+        self.store.update_pk(&r.guid, id, pk, &info_str).await
+      }
+    };
+    if let Err(err) = res {
+      error!("store.update_pk failed: {}", err);
+      self.remove_if_same(id, &peer).await;
+      return register_pk_response::Result::SERVER_ERROR;
+    }
+    self.remove_if_same(old_id, &peer).await;
+    let mut ip_changes = IP_CHANGES.lock().await;
+    if let Some(x) = ip_changes.remove(old_id) {
+      ip_changes.insert(id.to_owned(), x);
+    }
+    drop(ip_changes);
+    info!("id changed: {} -> {}", old_id, id);
+    register_pk_response::Result::OK
+  }
+
+  /// Remove the id from the map if it is still the peer.
+  async fn remove_if_same(&self, id: &str, peer: &LockPeer) {
+    let mut map = self.map.write().await;
+    if map.get(id).is_some_and(|x| Arc::ptr_eq(x, peer)) {
+      map.remove(id);
+    }
+  }
+
+  /// Set the always-use-relay override of a registered peer, `None` to
+  /// follow the server setting.
+  ///
+  /// Returns false if the peer is unknown.
+  pub(crate) async fn set_always_use_relay(
+    &self,
+    id: &str,
+    always_use_relay: Option<bool>,
+  ) -> ResultType<bool> {
+    self
+      .update_info(id, |info| info.always_use_relay = always_use_relay)
+      .await
+  }
+
+  /// Disable a registered peer until the unix time (s), `u64::MAX` for
+  /// good, 0 to enable it.
+  ///
+  /// Returns false if the peer is unknown.
+  pub(crate) async fn set_disabled_until(
+    &self,
+    id: &str,
+    disabled_until: u64,
+  ) -> ResultType<bool> {
+    let Some(peer) = self.get(id).await else {
+      return Ok(false);
+    };
+    if !self
+      .update_info(id, |info| info.disabled_until = disabled_until)
+      .await?
+    {
+      return Ok(false);
+    }
+    let guid = peer.read().await.guid.clone();
+    let mut user_status = USER_STATUS.write().await;
+    if disabled_until > 0 {
+      user_status.insert(guid, (id.to_owned(), disabled_until));
+    } else {
+      user_status.remove(&guid);
+    }
+    Ok(true)
+  }
+
+  /// The id of the peer with the guid.
+  pub(crate) async fn id_of_guid(
+    &self,
+    guid: &[u8],
+  ) -> ResultType<Option<String>> {
+    if let Some((id, _)) = USER_STATUS.read().await.get(guid) {
+      return Ok(Some(id.clone()));
+    }
+    Ok(self.store.get_peer_by_guid(guid).await?.map(|x| x.id))
+  }
+
+  /// The disabled devices, `(guid, id, disabled until)`.
+  pub(crate) async fn list_disabled(&self) -> Vec<(Vec<u8>, String, u64)> {
+    USER_STATUS
+      .read()
+      .await
+      .iter()
+      .map(|(guid, (id, until))| (guid.clone(), id.clone(), *until))
+      .collect()
+  }
+
+  /// Enable the devices whose suspension is over, returns the number
+  /// enabled.
+  ///
+  /// The devices no longer in the store are dropped from the list, a
+  /// failed one is tried again at the next sweep.
+  pub(crate) async fn expire_disabled(&self) -> usize {
+    let now = get_unix_time();
+    let expired: Vec<(Vec<u8>, String)> = USER_STATUS
+      .read()
+      .await
+      .iter()
+      .filter(|(_, (_, until))| *until <= now)
+      .map(|(guid, (id, _))| (guid.clone(), id.clone()))
+      .collect();
+    let mut n = 0;
+    for (guid, id) in expired {
+      match self.set_disabled_until(&id, 0).await {
+        Ok(true) => n += 1,
+        Ok(false) => {
+          debug!("disabled device {} is gone", id);
+          USER_STATUS.write().await.remove(&guid);
+        }
+        Err(err) => error!("failed to enable device {}: {}", id, err),
+      }
+    }
+    n
+  }
+
+  /// Change the info of a registered peer and save it.
+  ///
+  /// Returns false if the peer is unknown.
+  async fn update_info(
+    &self,
+    id: &str,
+    f: impl FnOnce(&mut PeerInfo),
+  ) -> ResultType<bool> {
+    let Some(peer) = self.get(id).await else {
+      return Ok(false);
+    };
+    let mut w = peer.write().await;
+    if w.guid.is_empty() {
+      return Ok(false);
+    }
+    f(&mut w.peer_info);
+    let info_str = serde_json::to_string(&w.peer_info).unwrap_or_default();
+    self.store.update_pk(&w.guid, id, &w.pk, &info_str).await?;
+    Ok(true)
+  }
+
+  /// Flush the peer store, the map itself is written through on every
+  /// change.
+  pub(crate) async fn flush(&self) -> ResultType<()> {
+    self.store.flush().await
+  }
+
+  /// Remove the stale peers from memory, returns the number removed:
+  ///
+  /// - the placeholders of the ids never registered, e.g. added by
+  ///   [`PeerMap::get_or`] for a rejected registration
+  /// - the peers not registered within `ttl`, if the store is persistent,
+  ///   they are loaded again on demand
+  pub(crate) async fn evict(&self, ttl: Option<Duration>) -> usize {
+    let ttl = ttl.filter(|_| self.store.is_persistent());
+    let is_stale = |peer: &Peer| {
+      let elapsed = peer.last_register_time.elapsed();
+      (peer.guid.is_empty() && elapsed.as_millis() >= REG_TIMEOUT as u128)
+        || ttl.map(|ttl| elapsed >= ttl).unwrap_or(false)
+    };
+    let peers: Vec<(String, LockPeer)> = self
+      .map
+      .read()
+      .await
+      .iter()
+      .map(|(id, peer)| (id.clone(), peer.clone()))
+      .collect();
+    let mut stale = Vec::new();
+    for (id, peer) in peers {
+      if is_stale(&*peer.read().await) {
+        stale.push((id, peer));
+      }
+    }
+    if stale.is_empty() {
+      return 0;
+    }
+    let mut map = self.map.write().await;
+    let mut n = 0;
+    for (id, peer) in stale {
+      // skip the peers replaced or being updated since
+      let removable = match (map.get(&id), peer.try_read()) {
+        (Some(p), Ok(r)) => Arc::ptr_eq(p, &peer) && is_stale(&r),
+        _ => false,
+      };
+      if removable {
+        map.remove(&id);
+        n += 1;
+      }
+    }
+    n
   }
 
   #[inline]
   pub(crate) async fn get_in_memory(&self, id: &str) -> Option<LockPeer> {
     self.map.read().await.get(id).cloned()
   }
+}
 
-  #[inline]
-  pub(crate) async fn is_in_memory(&self, id: &str) -> bool {
-    self.map.read().await.contains_key(id)
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::peer_store::{tests::temp_path, MemoryStore};
+  use nimbus_common::tokio;
+
+  /// Register the peer from 1.1.1.1 like a `RegisterPk`.
+  async fn register(
+    pm: &mut PeerMap,
+    id: &str,
+    uuid: &str,
+    pk: &str,
+  ) -> LockPeer {
+    let peer = pm.get_or(id).await.unwrap();
+    let res = pm
+      .update_pk(
+        id.to_owned(),
+        peer.clone(),
+        "1.1.1.1:1000".parse().unwrap(),
+        Bytes::from(uuid.to_owned()),
+        Bytes::from(pk.to_owned()),
+        "1.1.1.1".to_owned(),
+      )
+      .await;
+    assert_eq!(res, register_pk_response::Result::OK);
+    peer
+  }
+
+  #[test]
+  fn test_change_id() {
+    test_change_id_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_change_id_async() {
+    let mut pm = PeerMap::new(Box::<MemoryStore>::default()).await.unwrap();
+    register(&mut pm, "111111", "u1", "p1").await;
+    register(&mut pm, "222222", "u2", "p2").await;
+
+    // not the owner
+    assert_eq!(
+      pm.change_id("111111", "333333", b"u2", b"p2").await,
+      register_pk_response::Result::UUID_MISMATCH
+    );
+    assert_eq!(
+      pm.change_id("999999", "333333", b"u1", b"p1").await,
+      register_pk_response::Result::UUID_MISMATCH
+    );
+    // taken
+    assert_eq!(
+      pm.change_id("111111", "222222", b"u1", b"p1").await,
+      register_pk_response::Result::ID_EXISTS
+    );
+
+    IP_CHANGES
+      .lock()
+      .await
+      .insert("111111".to_owned(), (Instant::now(), HashMap::new()));
+    assert_eq!(
+      pm.change_id("111111", "333333", b"u1", b"p1").await,
+      register_pk_response::Result::OK
+    );
+    assert!(pm.get_in_memory("111111").await.is_none());
+    // the side entries follow the id
+    let ip_changes = IP_CHANGES.lock().await;
+    assert!(!ip_changes.contains_key("111111"));
+    assert!(ip_changes.contains_key("333333"));
+    drop(ip_changes);
+    assert!(pm.store.get_peer("111111").await.unwrap().is_none());
+    let peer = pm.get("333333").await.unwrap();
+    assert_eq!(peer.read().await.uuid, "u1");
+    let record = pm.store.get_peer("333333").await.unwrap().unwrap();
+    assert_eq!(record.guid, peer.read().await.guid);
+    assert_eq!(record.uuid, b"u1");
+  }
+
+  #[test]
+  fn test_always_use_relay() {
+    test_always_use_relay_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_always_use_relay_async() {
+    let mut pm = PeerMap::new(Box::<MemoryStore>::default()).await.unwrap();
+    let peer = register(&mut pm, "111111", "u1", "p1").await;
+    assert!(!pm.set_always_use_relay("222222", Some(true)).await.unwrap());
+    assert!(pm.set_always_use_relay("111111", Some(true)).await.unwrap());
+    assert_eq!(peer.read().await.peer_info.always_use_relay, Some(true));
+    // persisted with the peer
+    let record = pm.store.get_peer("111111").await.unwrap().unwrap();
+    let info: PeerInfo = serde_json::from_str(&record.info).unwrap();
+    assert_eq!(info.always_use_relay, Some(true));
+
+    assert!(pm.set_always_use_relay("111111", None).await.unwrap());
+    let record = pm.store.get_peer("111111").await.unwrap().unwrap();
+    assert!(!record.info.contains("always_use_relay"));
+  }
+
+  #[test]
+  fn test_disabled() {
+    test_disabled_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_disabled_async() {
+    let path = temp_path("disabled.json");
+    let url = format!("json://{}", path);
+    let store = crate::peer_store::open(&url).await.unwrap();
+    let mut pm = PeerMap::new(store).await.unwrap();
+    let peer = register(&mut pm, "333333", "u1", "p1").await;
+    let guid = peer.read().await.guid.clone();
+    assert!(!peer.read().await.peer_info.is_disabled());
+    assert!(!pm.set_disabled_until("444444", u64::MAX).await.unwrap());
+    assert!(pm.set_disabled_until("333333", u64::MAX).await.unwrap());
+    assert!(peer.read().await.peer_info.is_disabled());
+    drop(pm);
+
+    // loaded from the store
+    USER_STATUS.write().await.remove(&guid);
+    let store = crate::peer_store::open(&url).await.unwrap();
+    let pm = PeerMap::new(store).await.unwrap();
+    assert!(pm.list_disabled().await.iter().any(|x| x.1 == "333333"));
+    assert_eq!(
+      pm.id_of_guid(&guid).await.unwrap().as_deref(),
+      Some("333333")
+    );
+    let peer = pm.get("333333").await.unwrap();
+    assert!(peer.read().await.peer_info.is_disabled());
+
+    // the suspension is over
+    let until = get_unix_time() - 1;
+    assert!(pm.set_disabled_until("333333", until).await.unwrap());
+    assert!(!peer.read().await.peer_info.is_disabled());
+    assert!(pm.expire_disabled().await >= 1);
+    assert!(!USER_STATUS.read().await.contains_key(&guid));
+    // not in the store any more
+    USER_STATUS
+      .write()
+      .await
+      .insert(vec![0xde, 0xad], ("555555".to_owned(), until));
+    pm.expire_disabled().await;
+    assert!(!USER_STATUS.read().await.contains_key(&vec![0xde, 0xad]));
+    let record = pm.store.get_peer("333333").await.unwrap().unwrap();
+    assert!(!record.info.contains("disabled_until"));
+    std::fs::remove_file(&path).ok();
+  }
+
+  #[test]
+  fn test_evict() {
+    test_evict_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_evict_async() {
+    let path = temp_path("evict.json");
+    let store = crate::peer_store::open(&format!("json://{}", path))
+      .await
+      .unwrap();
+    let mut pm = PeerMap::new(store).await.unwrap();
+    let peer = register(&mut pm, "111111", "u1", "p1").await;
+    // never registered
+    pm.get_or("222222").await.unwrap();
+
+    assert_eq!(pm.evict(Some(Duration::from_secs(60))).await, 1);
+    assert!(pm.get_in_memory("111111").await.is_some());
+    assert!(pm.get_in_memory("222222").await.is_none());
+
+    peer.write().await.last_register_time = get_expired_time();
+    assert_eq!(pm.evict(None).await, 0);
+    assert_eq!(pm.evict(Some(Duration::from_secs(60))).await, 1);
+    assert!(pm.get_in_memory("111111").await.is_none());
+    // loaded again from the store
+    assert_eq!(pm.get("111111").await.unwrap().read().await.uuid, "u1");
+    std::fs::remove_file(&path).ok();
+
+    // the peers are only in memory without a persistent store
+    let pm = PeerMap::new(Box::<MemoryStore>::default()).await.unwrap();
+    pm.get_or("111111").await.unwrap().write().await.guid = vec![1];
+    assert_eq!(pm.evict(Some(Duration::from_secs(0))).await, 0);
   }
 }
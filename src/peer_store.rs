@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use nimbus_common::{anyhow::bail, ResultType};
+use serde_derive::{Deserialize, Serialize};
+
+mod json_file;
+pub use json_file::JsonFileStore;
+mod memory;
+pub use memory::MemoryStore;
+mod sqlite;
+pub use sqlite::SqliteStore;
+
+/// A peer record persisted in the store.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerRecord {
+  pub guid: Vec<u8>,
+  pub id: String,
+  pub uuid: Vec<u8>,
+  pub pk: Vec<u8>,
+  /// `PeerInfo` in json
+  pub info: String,
+}
+
+/// Persistent storage of the registered peers behind `PeerMap`.
+#[async_trait]
+pub trait PeerStore: Send + Sync {
+  /// Insert a new peer, returns its guid.
+  ///
+  /// Fails if the id already exists.
+  async fn insert_peer(
+    &self,
+    id: &str,
+    uuid: &[u8],
+    pk: &[u8],
+    info: &str,
+  ) -> ResultType<Vec<u8>>;
+
+  /// Update the id, public key and info of the peer with the guid.
+  async fn update_pk(
+    &self,
+    guid: &[u8],
+    id: &str,
+    pk: &[u8],
+    info: &str,
+  ) -> ResultType<()>;
+
+  async fn get_peer(&self, id: &str) -> ResultType<Option<PeerRecord>>;
+
+  async fn get_peer_by_guid(
+    &self,
+    guid: &[u8],
+  ) -> ResultType<Option<PeerRecord>>;
+
+  async fn delete_peer(&self, id: &str) -> ResultType<()>;
+
+  /// All the peers, ordered by guid.
+  async fn list_peers(&self) -> ResultType<Vec<PeerRecord>>;
+
+  /// The peers disabled or suspended by the admin, i.e. with a non-zero
+  /// `disabled_until` in their info, ordered by guid.
+  async fn list_disabled_peers(&self) -> ResultType<Vec<PeerRecord>>;
+
+  /// Whether the peers are kept across restarts.
+  fn is_persistent(&self) -> bool {
+    true
+  }
+
+  /// Flush the pending writes before the server exits.
+  async fn flush(&self) -> ResultType<()> {
+    Ok(())
+  }
+}
+
+/// Open the peer store selected by the url:
+///
+/// - `memory`: in-memory, lost on restart
+/// - `json://<path>`: json file
+/// - `sqlite://<path>`: sqlite database
+pub async fn open(url: &str) -> ResultType<Box<dyn PeerStore>> {
+  if url == "memory" {
+    Ok(Box::<MemoryStore>::default())
+  } else if let Some(path) = url.strip_prefix("json://") {
+    Ok(Box::new(JsonFileStore::open(path).await?))
+  } else if url.starts_with("sqlite:") {
+    Ok(Box::new(SqliteStore::new(url).await?))
+  } else {
+    bail!("unsupported peer store: {}", url)
+  }
+}
+
+/// The guid of a peer is a 64 bits sequence number in big endian.
+#[inline]
+pub(crate) fn guid_to_bytes(guid: i64) -> Vec<u8> {
+  guid.to_be_bytes().to_vec()
+}
+
+/// Whether the info of a record has a non-zero `disabled_until`.
+fn is_disabled_info(info: &str) -> bool {
+  serde_json::from_str::<serde_json::Value>(info)
+    .ok()
+    .and_then(|x| x.get("disabled_until")?.as_u64())
+    .is_some_and(|x| x > 0)
+}
+
+#[inline]
+pub(crate) fn bytes_to_guid(guid: &[u8]) -> i64 {
+  let mut bytes = [0u8; 8];
+  let n = guid.len().min(8);
+  bytes[8 - n..].copy_from_slice(&guid[guid.len() - n..]);
+  i64::from_be_bytes(bytes)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+  use nimbus_common::tokio;
+
+  /// The conformance suite every backend has to pass.
+  async fn check_store(store: &dyn PeerStore) {
+    assert!(store.get_peer("123456").await.unwrap().is_none());
+    assert!(store.list_peers().await.unwrap().is_empty());
+
+    let guid1 = store
+      .insert_peer("123456", b"uuid1", b"pk1", "{}")
+      .await
+      .unwrap();
+    let guid2 = store
+      .insert_peer("654321", b"uuid2", b"pk2", "{}")
+      .await
+      .unwrap();
+    assert_ne!(guid1, guid2);
+    // the id is unique
+    assert!(store
+      .insert_peer("123456", b"uuid3", b"pk3", "{}")
+      .await
+      .is_err());
+
+    let peer = store.get_peer("123456").await.unwrap().unwrap();
+    assert_eq!(
+      peer,
+      PeerRecord {
+        guid: guid1.clone(),
+        id: "123456".to_owned(),
+        uuid: b"uuid1".to_vec(),
+        pk: b"pk1".to_vec(),
+        info: "{}".to_owned(),
+      }
+    );
+
+    store
+      .update_pk(&guid1, "123456", b"pk1-new", r#"{"ip":"1.1.1.1"}"#)
+      .await
+      .unwrap();
+    let peer = store.get_peer("123456").await.unwrap().unwrap();
+    assert_eq!(peer.guid, guid1);
+    assert_eq!(peer.uuid, b"uuid1");
+    assert_eq!(peer.pk, b"pk1-new");
+    assert_eq!(peer.info, r#"{"ip":"1.1.1.1"}"#);
+
+    let peers = store.list_peers().await.unwrap();
+    assert_eq!(
+      peers.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(),
+      vec!["123456", "654321"]
+    );
+
+    let peer = store.get_peer_by_guid(&guid2).await.unwrap().unwrap();
+    assert_eq!(peer.id, "654321");
+    assert!(store.get_peer_by_guid(&[9]).await.unwrap().is_none());
+
+    assert!(store.list_disabled_peers().await.unwrap().is_empty());
+    store
+      .update_pk(&guid2, "654321", b"pk2", r#"{"disabled_until":1}"#)
+      .await
+      .unwrap();
+    let peers = store.list_disabled_peers().await.unwrap();
+    assert_eq!(peers.len(), 1);
+    assert_eq!(peers[0].id, "654321");
+
+    store.delete_peer("123456").await.unwrap();
+    assert!(store.get_peer("123456").await.unwrap().is_none());
+    assert_eq!(store.list_peers().await.unwrap().len(), 1);
+    // deleting an unknown id is not an error
+    store.delete_peer("123456").await.unwrap();
+  }
+
+  /// A fresh path in the temp dir, for the tests of every module.
+  pub(crate) fn temp_path(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!(
+      "nimbus_{}_{}",
+      std::process::id(),
+      name
+    ));
+    std::fs::remove_file(&path).ok();
+    path.to_string_lossy().to_string()
+  }
+
+  #[test]
+  fn test_memory_store() {
+    test_memory_store_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_memory_store_async() {
+    check_store(open("memory").await.unwrap().as_ref()).await;
+  }
+
+  #[test]
+  fn test_sqlite_store() {
+    test_sqlite_store_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_sqlite_store_async() {
+    let path = temp_path("peer.sqlite3");
+    let url = format!("sqlite://{}", path);
+    check_store(open(&url).await.unwrap().as_ref()).await;
+    // reopen
+    let store = open(&url).await.unwrap();
+    assert_eq!(store.list_peers().await.unwrap().len(), 1);
+    std::fs::remove_file(path).ok();
+  }
+
+  #[test]
+  fn test_json_file_store() {
+    test_json_file_store_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_json_file_store_async() {
+    let path = temp_path("peer.json");
+    let url = format!("json://{}", path);
+    check_store(open(&url).await.unwrap().as_ref()).await;
+    // reopen, the guid sequence continues
+    let store = open(&url).await.unwrap();
+    assert_eq!(store.list_peers().await.unwrap().len(), 1);
+    let guid = store.insert_peer("111111", b"u", b"p", "{}").await.unwrap();
+    assert_eq!(bytes_to_guid(&guid), 3);
+    std::fs::remove_file(path).ok();
+  }
+}
@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use nimbus_common::{
+  logger::*,
+  tokio::{self, sync::Mutex},
+  ResultType,
+};
+
+use super::{memory::Peers, PeerRecord, PeerStore};
+
+/// Json file storage of the registered peers for small deployments,
+/// the whole file is rewritten on every change.
+pub struct JsonFileStore {
+  path: PathBuf,
+  peers: Mutex<Peers>,
+}
+
+impl JsonFileStore {
+  pub async fn open(path: &str) -> ResultType<JsonFileStore> {
+    let path = PathBuf::from(path);
+    let peers = match tokio::fs::read(&path).await {
+      Ok(data) => serde_json::from_slice(&data)?,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+        Peers::default()
+      }
+      Err(err) => return Err(err.into()),
+    };
+    info!("json peer store {} loaded", path.display());
+    Ok(JsonFileStore {
+      path,
+      peers: Mutex::new(peers),
+    })
+  }
+
+  /// Write to a temporary file first, so a crash never leaves a truncated
+  /// file behind.
+  async fn save(&self, peers: &Peers) -> ResultType<()> {
+    let data = serde_json::to_vec_pretty(peers)?;
+    let tmp = self.path.with_extension("tmp");
+    tokio::fs::write(&tmp, data).await?;
+    tokio::fs::rename(&tmp, &self.path).await?;
+    Ok(())
+  }
+}
+
+#[async_trait]
+impl PeerStore for JsonFileStore {
+  async fn insert_peer(
+    &self,
+    id: &str,
+    uuid: &[u8],
+    pk: &[u8],
+    info: &str,
+  ) -> ResultType<Vec<u8>> {
+    let mut peers = self.peers.lock().await;
+    let guid = peers.insert_peer(id, uuid, pk, info)?;
+    self.save(&peers).await?;
+    Ok(guid)
+  }
+
+  async fn update_pk(
+    &self,
+    guid: &[u8],
+    id: &str,
+    pk: &[u8],
+    info: &str,
+  ) -> ResultType<()> {
+    let mut peers = self.peers.lock().await;
+    peers.update_pk(guid, id, pk, info)?;
+    self.save(&peers).await
+  }
+
+  async fn get_peer(&self, id: &str) -> ResultType<Option<PeerRecord>> {
+    Ok(self.peers.lock().await.peers.get(id).cloned())
+  }
+
+  async fn get_peer_by_guid(
+    &self,
+    guid: &[u8],
+  ) -> ResultType<Option<PeerRecord>> {
+    Ok(self.peers.lock().await.get_peer_by_guid(guid))
+  }
+
+  async fn delete_peer(&self, id: &str) -> ResultType<()> {
+    let mut peers = self.peers.lock().await;
+    if peers.peers.remove(id).is_some() {
+      self.save(&peers).await?;
+    }
+    Ok(())
+  }
+
+  async fn list_peers(&self) -> ResultType<Vec<PeerRecord>> {
+    Ok(self.peers.lock().await.list_peers())
+  }
+
+  async fn list_disabled_peers(&self) -> ResultType<Vec<PeerRecord>> {
+    Ok(self.peers.lock().await.list_disabled_peers())
+  }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use nimbus_common::{anyhow::bail, tokio::sync::Mutex, ResultType};
+use serde_derive::{Deserialize, Serialize};
+
+use super::{guid_to_bytes, is_disabled_info, PeerRecord, PeerStore};
+
+/// The registered peers and the last allocated guid.
+#[derive(Default, Serialize, Deserialize)]
+pub(super) struct Peers {
+  pub(super) last_guid: i64,
+  pub(super) peers: HashMap<String, PeerRecord>,
+}
+
+impl Peers {
+  pub(super) fn insert_peer(
+    &mut self,
+    id: &str,
+    uuid: &[u8],
+    pk: &[u8],
+    info: &str,
+  ) -> ResultType<Vec<u8>> {
+    if self.peers.contains_key(id) {
+      bail!("peer {} already exists", id);
+    }
+    self.last_guid += 1;
+    let guid = guid_to_bytes(self.last_guid);
+    self.peers.insert(
+      id.to_owned(),
+      PeerRecord {
+        guid: guid.clone(),
+        id: id.to_owned(),
+        uuid: uuid.to_vec(),
+        pk: pk.to_vec(),
+        info: info.to_owned(),
+      },
+    );
+    Ok(guid)
+  }
+
+  pub(super) fn update_pk(
+    &mut self,
+    guid: &[u8],
+    id: &str,
+    pk: &[u8],
+    info: &str,
+  ) -> ResultType<()> {
+    let Some(old_id) = self
+      .peers
+      .values()
+      .find(|p| p.guid == guid)
+      .map(|p| p.id.clone())
+    else {
+      return Ok(());
+    };
+    if old_id != id && self.peers.contains_key(id) {
+      bail!("peer {} already exists", id);
+    }
+    if let Some(mut peer) = self.peers.remove(&old_id) {
+      peer.id = id.to_owned();
+      peer.pk = pk.to_vec();
+      peer.info = info.to_owned();
+      self.peers.insert(id.to_owned(), peer);
+    }
+    Ok(())
+  }
+
+  pub(super) fn get_peer_by_guid(&self, guid: &[u8]) -> Option<PeerRecord> {
+    self.peers.values().find(|p| p.guid == guid).cloned()
+  }
+
+  pub(super) fn list_disabled_peers(&self) -> Vec<PeerRecord> {
+    let mut peers = self.list_peers();
+    peers.retain(|p| is_disabled_info(&p.info));
+    peers
+  }
+
+  pub(super) fn list_peers(&self) -> Vec<PeerRecord> {
+    let mut peers: Vec<PeerRecord> = self.peers.values().cloned().collect();
+    peers.sort_by(|a, b| a.guid.cmp(&b.guid));
+    peers
+  }
+}
+
+/// In-memory storage of the registered peers, lost on restart.
+#[derive(Default)]
+pub struct MemoryStore {
+  peers: Mutex<Peers>,
+}
+
+#[async_trait]
+impl PeerStore for MemoryStore {
+  fn is_persistent(&self) -> bool {
+    false
+  }
+
+  async fn insert_peer(
+    &self,
+    id: &str,
+    uuid: &[u8],
+    pk: &[u8],
+    info: &str,
+  ) -> ResultType<Vec<u8>> {
+    self.peers.lock().await.insert_peer(id, uuid, pk, info)
+  }
+
+  async fn update_pk(
+    &self,
+    guid: &[u8],
+    id: &str,
+    pk: &[u8],
+    info: &str,
+  ) -> ResultType<()> {
+    self.peers.lock().await.update_pk(guid, id, pk, info)
+  }
+
+  async fn get_peer(&self, id: &str) -> ResultType<Option<PeerRecord>> {
+    Ok(self.peers.lock().await.peers.get(id).cloned())
+  }
+
+  async fn get_peer_by_guid(
+    &self,
+    guid: &[u8],
+  ) -> ResultType<Option<PeerRecord>> {
+    Ok(self.peers.lock().await.get_peer_by_guid(guid))
+  }
+
+  async fn delete_peer(&self, id: &str) -> ResultType<()> {
+    self.peers.lock().await.peers.remove(id);
+    Ok(())
+  }
+
+  async fn list_peers(&self) -> ResultType<Vec<PeerRecord>> {
+    Ok(self.peers.lock().await.list_peers())
+  }
+
+  async fn list_disabled_peers(&self) -> ResultType<Vec<PeerRecord>> {
+    Ok(self.peers.lock().await.list_disabled_peers())
+  }
+}
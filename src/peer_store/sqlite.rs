@@ -0,0 +1,174 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use nimbus_common::{logger::*, ResultType};
+use sqlx::{
+  sqlite::{
+    SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteRow,
+  },
+  Row, SqlitePool,
+};
+
+use super::{bytes_to_guid, guid_to_bytes, PeerRecord, PeerStore};
+
+/// max connections of the sqlite pool
+static MAX_DATABASE_CONNECTIONS: u32 = 1;
+
+/// Sqlite storage of the registered peers, the guid of a peer is its row id.
+pub struct SqliteStore {
+  pool: SqlitePool,
+}
+
+impl SqliteStore {
+  pub async fn new(url: &str) -> ResultType<SqliteStore> {
+    let options = SqliteConnectOptions::from_str(url)?
+      .create_if_missing(true)
+      .journal_mode(SqliteJournalMode::Wal);
+    let pool = SqlitePoolOptions::new()
+      .max_connections(MAX_DATABASE_CONNECTIONS)
+      .connect_with(options)
+      .await?;
+    info!("database {} connected", url);
+    let db = SqliteStore { pool };
+    db.create_tables().await?;
+    Ok(db)
+  }
+
+  async fn create_tables(&self) -> ResultType<()> {
+    sqlx::query(
+      "
+      create table if not exists peer (
+        guid integer primary key autoincrement,
+        id varchar(100) not null,
+        uuid blob not null,
+        pk blob not null,
+        created_at datetime not null default(current_timestamp),
+        info text not null
+      )
+      ",
+    )
+    .execute(&self.pool)
+    .await?;
+    sqlx::query("create unique index if not exists index_peer_id on peer (id)")
+      .execute(&self.pool)
+      .await?;
+    // the few disabled peers loaded on start
+    sqlx::query(
+      "
+      create index if not exists index_peer_disabled
+      on peer (guid) where json_extract(info, '$.disabled_until') > 0
+      ",
+    )
+    .execute(&self.pool)
+    .await?;
+    Ok(())
+  }
+}
+
+#[async_trait]
+impl PeerStore for SqliteStore {
+  async fn insert_peer(
+    &self,
+    id: &str,
+    uuid: &[u8],
+    pk: &[u8],
+    info: &str,
+  ) -> ResultType<Vec<u8>> {
+    let guid =
+      sqlx::query("insert into peer (id, uuid, pk, info) values (?, ?, ?, ?)")
+        .bind(id)
+        .bind(uuid)
+        .bind(pk)
+        .bind(info)
+        .execute(&self.pool)
+        .await?
+        .last_insert_rowid();
+    Ok(guid_to_bytes(guid))
+  }
+
+  async fn update_pk(
+    &self,
+    guid: &[u8],
+    id: &str,
+    pk: &[u8],
+    info: &str,
+  ) -> ResultType<()> {
+    sqlx::query("update peer set id = ?, pk = ?, info = ? where guid = ?")
+      .bind(id)
+      .bind(pk)
+      .bind(info)
+      .bind(bytes_to_guid(guid))
+      .execute(&self.pool)
+      .await?;
+    Ok(())
+  }
+
+  async fn get_peer(&self, id: &str) -> ResultType<Option<PeerRecord>> {
+    let row =
+      sqlx::query("select guid, id, uuid, pk, info from peer where id = ?")
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+    Ok(row.as_ref().map(to_record))
+  }
+
+  async fn get_peer_by_guid(
+    &self,
+    guid: &[u8],
+  ) -> ResultType<Option<PeerRecord>> {
+    let row =
+      sqlx::query("select guid, id, uuid, pk, info from peer where guid = ?")
+        .bind(bytes_to_guid(guid))
+        .fetch_optional(&self.pool)
+        .await?;
+    Ok(row.as_ref().map(to_record))
+  }
+
+  async fn delete_peer(&self, id: &str) -> ResultType<()> {
+    sqlx::query("delete from peer where id = ?")
+      .bind(id)
+      .execute(&self.pool)
+      .await?;
+    Ok(())
+  }
+
+  async fn list_peers(&self) -> ResultType<Vec<PeerRecord>> {
+    let rows =
+      sqlx::query("select guid, id, uuid, pk, info from peer order by guid")
+        .fetch_all(&self.pool)
+        .await?;
+    Ok(rows.iter().map(to_record).collect())
+  }
+
+  async fn list_disabled_peers(&self) -> ResultType<Vec<PeerRecord>> {
+    let rows = sqlx::query(
+      "
+      select guid, id, uuid, pk, info from peer
+      where json_extract(info, '$.disabled_until') > 0 order by guid
+      ",
+    )
+    .fetch_all(&self.pool)
+    .await?;
+    Ok(rows.iter().map(to_record).collect())
+  }
+
+  /// Move the write-ahead log into the database file and close the pool.
+  async fn flush(&self) -> ResultType<()> {
+    sqlx::query("pragma wal_checkpoint(truncate)")
+      .execute(&self.pool)
+      .await?;
+    self.pool.close().await;
+    Ok(())
+  }
+}
+
+#[inline]
+fn to_record(row: &SqliteRow) -> PeerRecord {
+  PeerRecord {
+    guid: guid_to_bytes(row.get("guid")),
+    id: row.get("id"),
+    uuid: row.get("uuid"),
+    pk: row.get("pk"),
+    info: row.get("info"),
+  }
+}
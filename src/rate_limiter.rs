@@ -0,0 +1,370 @@
+use std::{
+  borrow::Borrow,
+  collections::HashMap,
+  hash::Hash,
+  net::{IpAddr, Ipv4Addr, Ipv6Addr},
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    Mutex,
+  },
+  time::Instant,
+};
+
+use nimbus_common::protos::rendezvous::{
+  rendezvous_message, RendezvousMessage,
+};
+
+use crate::config::{BucketConfig, LimitsConfig};
+
+/// the map is swept of the full buckets when it grows beyond this size
+static MIN_SWEEP_SIZE: usize = 1024;
+
+#[derive(Debug)]
+struct Bucket {
+  tokens: f64,
+  last: Instant,
+}
+
+#[derive(Debug)]
+struct Buckets<K> {
+  config: BucketConfig,
+  map: HashMap<K, Bucket>,
+  /// the size to sweep at next
+  sweep_size: usize,
+}
+
+/// Token buckets by key, each holds up to `burst` tokens and is refilled
+/// at `rate` tokens per second, a request takes one.
+#[derive(Debug)]
+pub struct RateLimiter<K> {
+  buckets: Mutex<Buckets<K>>,
+}
+
+impl<K: Hash + Eq> RateLimiter<K> {
+  pub fn new(config: BucketConfig) -> Self {
+    RateLimiter {
+      buckets: Mutex::new(Buckets {
+        config,
+        map: HashMap::new(),
+        sweep_size: MIN_SWEEP_SIZE,
+      }),
+    }
+  }
+
+  /// Take a token of `key`, false if the bucket is empty.
+  pub fn check(&self, key: K) -> bool {
+    self.check_at(key, Instant::now())
+  }
+
+  fn check_at(&self, key: K, now: Instant) -> bool {
+    let mut buckets = self.buckets.lock().unwrap();
+    let config = buckets.config;
+    if config.rate <= 0. {
+      return true;
+    }
+    if buckets.map.len() >= buckets.sweep_size {
+      buckets.sweep(now);
+    }
+    let bucket = buckets.map.entry(key).or_insert(Bucket {
+      tokens: config.burst,
+      last: now,
+    });
+    bucket.refill(config, now);
+    if bucket.tokens >= 1. {
+      bucket.tokens -= 1.;
+      true
+    } else {
+      false
+    }
+  }
+
+  /// Remove the buckets which are full again, i.e. idle for long enough,
+  /// returns the number removed.
+  pub fn evict_idle(&self) -> usize {
+    let mut buckets = self.buckets.lock().unwrap();
+    let n = buckets.map.len();
+    buckets.sweep(Instant::now());
+    n - buckets.map.len()
+  }
+
+  /// Change the rate and the burst, the buckets are kept and capped at the
+  /// new burst.
+  pub fn set_config(&self, config: BucketConfig) {
+    let mut buckets = self.buckets.lock().unwrap();
+    buckets.config = config;
+    for bucket in buckets.map.values_mut() {
+      bucket.tokens = bucket.tokens.min(config.burst);
+    }
+  }
+
+  /// Forget the bucket of `key`.
+  pub fn remove<Q>(&self, key: &Q)
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.buckets.lock().unwrap().map.remove(key);
+  }
+
+  pub fn clear(&self) {
+    self.buckets.lock().unwrap().map.clear();
+  }
+
+  pub fn len(&self) -> usize {
+    self.buckets.lock().unwrap().map.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+impl Bucket {
+  fn refill(&mut self, config: BucketConfig, now: Instant) {
+    let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+    self.tokens = (self.tokens + elapsed * config.rate).min(config.burst);
+    self.last = now;
+  }
+}
+
+impl<K> Buckets<K> {
+  fn sweep(&mut self, now: Instant) {
+    let config = self.config;
+    self.map.retain(|_, bucket| {
+      bucket.refill(config, now);
+      bucket.tokens < config.burst
+    });
+    self.sweep_size = (self.map.len() * 2).max(MIN_SWEEP_SIZE);
+  }
+}
+
+/// The rate limits of the requests, shared by udp, tcp and websocket.
+#[derive(Debug)]
+pub struct Limiter {
+  /// every message of an ip but the heartbeats
+  ip: RateLimiter<IpAddr>,
+  /// every message of a subnet but the heartbeats
+  subnet: RateLimiter<IpAddr>,
+  /// `RegisterPeer` of an id
+  heartbeat: RateLimiter<String>,
+  /// `RegisterPeer` of an ip
+  heartbeat_ip: RateLimiter<IpAddr>,
+  /// `RegisterPk` of an ip
+  register_ip: RateLimiter<IpAddr>,
+  /// `RegisterPk` of an id
+  register_id: RateLimiter<String>,
+  subnet_prefix_v4: AtomicU32,
+  subnet_prefix_v6: AtomicU32,
+}
+
+impl Limiter {
+  pub fn new(config: &LimitsConfig) -> Self {
+    Limiter {
+      ip: RateLimiter::new(config.ip),
+      subnet: RateLimiter::new(config.subnet),
+      heartbeat: RateLimiter::new(config.heartbeat),
+      heartbeat_ip: RateLimiter::new(config.heartbeat_ip),
+      register_ip: RateLimiter::new(config.register_ip),
+      register_id: RateLimiter::new(config.register_id),
+      subnet_prefix_v4: AtomicU32::new(config.subnet_prefix_v4.min(32)),
+      subnet_prefix_v6: AtomicU32::new(config.subnet_prefix_v6.min(128)),
+    }
+  }
+
+  /// Apply the reloaded limits, the buckets are kept but those of the
+  /// subnets if their prefix changes.
+  pub fn update(&self, config: &LimitsConfig) {
+    self.ip.set_config(config.ip);
+    self.subnet.set_config(config.subnet);
+    self.heartbeat.set_config(config.heartbeat);
+    self.heartbeat_ip.set_config(config.heartbeat_ip);
+    self.register_ip.set_config(config.register_ip);
+    self.register_id.set_config(config.register_id);
+    let v4 = config.subnet_prefix_v4.min(32);
+    let v6 = config.subnet_prefix_v6.min(128);
+    let v4_changed = self.subnet_prefix_v4.swap(v4, Ordering::Relaxed) != v4;
+    let v6_changed = self.subnet_prefix_v6.swap(v6, Ordering::Relaxed) != v6;
+    if v4_changed || v6_changed {
+      self.subnet.clear();
+    }
+  }
+
+  /// Whether a message from the ip is let in, the heartbeats are limited
+  /// by id and by a larger bucket of the ip, the clients behind a NAT
+  /// share an ip.
+  pub fn check_message(&self, ip: IpAddr, msg: &RendezvousMessage) -> bool {
+    match &msg.union {
+      Some(rendezvous_message::Union::RegisterPeer(rp)) => {
+        self.heartbeat_ip.check(ip.to_canonical())
+          && self.heartbeat.check(rp.id.clone())
+      }
+      _ => self.check_ip(ip),
+    }
+  }
+
+  /// Whether a message or a connection from the ip is let in.
+  pub fn check_ip(&self, ip: IpAddr) -> bool {
+    let ip = ip.to_canonical();
+    self.ip.check(ip) && self.subnet.check(self.subnet_of(ip))
+  }
+
+  /// Whether the ip can register the id.
+  pub fn check_register(&self, ip: IpAddr, id: &str) -> bool {
+    self.register_ip.check(ip.to_canonical())
+      && self.register_id.check(id.to_owned())
+  }
+
+  /// Forget the `RegisterPk` bucket of an id, e.g. renamed.
+  pub fn remove_id(&self, id: &str) {
+    self.register_id.remove(id);
+  }
+
+  /// Remove the idle buckets, returns the number removed.
+  pub fn evict_idle(&self) -> usize {
+    self.ip.evict_idle()
+      + self.subnet.evict_idle()
+      + self.heartbeat.evict_idle()
+      + self.heartbeat_ip.evict_idle()
+      + self.register_ip.evict_idle()
+      + self.register_id.evict_idle()
+  }
+
+  fn subnet_of(&self, ip: IpAddr) -> IpAddr {
+    match ip {
+      IpAddr::V4(ip) => {
+        let prefix = self.subnet_prefix_v4.load(Ordering::Relaxed);
+        let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+        IpAddr::V4(Ipv4Addr::from(u32::from(ip) & mask))
+      }
+      IpAddr::V6(ip) => {
+        let prefix = self.subnet_prefix_v6.load(Ordering::Relaxed);
+        let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+        IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask))
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use nimbus_common::protos::rendezvous::RegisterPeer;
+
+  use super::*;
+
+  #[test]
+  fn test_rate_limiter() {
+    let limiter = RateLimiter::new(BucketConfig {
+      rate: 1.,
+      burst: 3.,
+    });
+    let now = Instant::now();
+    for _ in 0..3 {
+      assert!(limiter.check_at("a", now));
+    }
+    assert!(!limiter.check_at("a", now));
+    // the other keys have their own buckets
+    assert!(limiter.check_at("b", now));
+    // refilled by one per second
+    let now = now + Duration::from_millis(1500);
+    assert!(limiter.check_at("a", now));
+    assert!(!limiter.check_at("a", now));
+
+    // the full buckets are evicted
+    assert_eq!(limiter.len(), 2);
+    let now = now + Duration::from_secs(3);
+    limiter.buckets.lock().unwrap().sweep(now);
+    assert!(limiter.is_empty());
+
+    // no limit
+    let limiter = RateLimiter::new(BucketConfig {
+      rate: 0.,
+      burst: 0.,
+    });
+    for _ in 0..100 {
+      assert!(limiter.check("a"));
+    }
+    assert!(limiter.is_empty());
+  }
+
+  #[test]
+  fn test_limiter() {
+    let limiter = Limiter::new(&LimitsConfig {
+      ip: BucketConfig {
+        rate: 1.,
+        burst: 2.,
+      },
+      subnet: BucketConfig {
+        rate: 1.,
+        burst: 3.,
+      },
+      ..Default::default()
+    });
+    let ip = |x: &str| x.parse::<IpAddr>().unwrap();
+    assert!(limiter.check_ip(ip("1.1.1.1")));
+    assert!(limiter.check_ip(ip("::ffff:1.1.1.1")));
+    assert!(!limiter.check_ip(ip("1.1.1.1")));
+    // the same /24
+    assert!(limiter.check_ip(ip("1.1.1.2")));
+    assert!(!limiter.check_ip(ip("1.1.1.3")));
+    assert!(limiter.check_ip(ip("1.1.2.1")));
+
+    assert_eq!(
+      limiter.subnet_of(ip("2001:db8:1:2:3::1")),
+      ip("2001:db8:1:2::")
+    );
+    let limiter = Limiter::new(&LimitsConfig {
+      subnet_prefix_v4: 0,
+      ..Default::default()
+    });
+    assert_eq!(limiter.subnet_of(ip("1.2.3.4")), ip("0.0.0.0"));
+  }
+
+  #[test]
+  fn test_heartbeat() {
+    let mut config = LimitsConfig {
+      ip: BucketConfig {
+        rate: 1.,
+        burst: 1.,
+      },
+      heartbeat: BucketConfig {
+        rate: 1.,
+        burst: 2.,
+      },
+      heartbeat_ip: BucketConfig {
+        rate: 1.,
+        burst: 100.,
+      },
+      ..Default::default()
+    };
+    let limiter = Limiter::new(&config);
+    let ip = "1.1.1.1".parse().unwrap();
+    let heartbeat = |id: &str| {
+      let mut msg = RendezvousMessage::new();
+      msg.set_register_peer(RegisterPeer {
+        id: id.to_owned(),
+        ..Default::default()
+      });
+      msg
+    };
+    assert!(limiter.check_message(ip, &RendezvousMessage::new()));
+    assert!(!limiter.check_message(ip, &RendezvousMessage::new()));
+    // the heartbeats of the clients behind the ip are let in
+    for i in 0..99 {
+      assert!(limiter.check_message(ip, &heartbeat(&i.to_string())));
+    }
+    assert!(limiter.check_message(ip, &heartbeat("0")));
+    // up to the cap of the ip
+    assert!(!limiter.check_message(ip, &heartbeat("100")));
+    let ip2 = "1.1.1.2".parse().unwrap();
+    assert!(!limiter.check_message(ip2, &heartbeat("0")));
+    assert!(limiter.check_message(ip2, &heartbeat("100")));
+
+    // the buckets are kept on reload
+    config.ip.burst = 10.;
+    limiter.update(&config);
+    assert!(!limiter.check_ip(ip));
+    assert!(!limiter.check_message(ip, &heartbeat("0")));
+  }
+}
@@ -0,0 +1,236 @@
+use std::{
+  collections::HashMap,
+  net::SocketAddr,
+  sync::atomic::{AtomicU64, Ordering},
+  time::Duration,
+};
+
+use nimbus_common::{
+  allow_err,
+  config::RELAY_PORT,
+  futures::StreamExt,
+  logger::*,
+  once_cell::sync::Lazy,
+  protobuf::Message,
+  protos::rendezvous::{rendezvous_message, HealthCheck, RendezvousMessage},
+  socket_client::check_port,
+  tcp::{listen_any, FramedStream},
+  tokio::{self, net::TcpStream, sync::Mutex, time::sleep},
+  ResultType,
+};
+
+/// Connections waiting for their counterpart, keyed by the relay session uuid,
+/// with the sequence number of the connection.
+static PEERS: Lazy<Mutex<HashMap<String, (u64, FramedStream)>>> =
+  Lazy::new(Default::default);
+/// sequence number of the waiting connections
+static CONN_SEQ: AtomicU64 = AtomicU64::new(0);
+/// How long a connection waits for its counterpart (ms).
+static PAIR_TIMEOUT: u64 = 30_000;
+/// token of the `HealthCheck`, matched with the echo
+static HEALTH_CHECK_SEQ: AtomicU64 = AtomicU64::new(0);
+/// pause after a failed accept (ms), e.g. out of file descriptors
+static ACCEPT_BACKOFF: u64 = 100;
+
+/// Relay server for the peers which can not connect to each other directly,
+/// e.g. both sides are behind `NatType::SYMMETRIC`.
+///
+/// Both peers connect to the relay and send a `RequestRelay` carrying the
+/// same session uuid, then the relay pipes the bytes between them until
+/// either side closes.
+///
+/// A `HealthCheck` as the first message is echoed back, the rendezvous
+/// server pings its relays with it.
+pub struct RelayServer;
+
+impl RelayServer {
+  #[tokio::main(flavor = "multi_thread")]
+  pub async fn start(port: i32) -> ResultType<()> {
+    let listener = listen_any(port as _).await?;
+    info!("Listening on relay tcp: {}", listener.local_addr()?);
+    loop {
+      match listener.accept().await {
+        Ok((stream, addr)) => {
+          stream.set_nodelay(true).ok();
+          tokio::spawn(handle_connection(stream, addr));
+        }
+        Err(err) => {
+          error!("relay listener accept failure: {}", err);
+          sleep(Duration::from_millis(ACCEPT_BACKOFF)).await;
+        }
+      }
+    }
+  }
+}
+
+async fn handle_connection(stream: TcpStream, addr: SocketAddr) {
+  debug!("Tcp connection from {:?}, relay listener", addr);
+  let mut stream = FramedStream::from(stream, addr);
+  let Some(Ok(bytes)) = stream.next_timeout(PAIR_TIMEOUT).await else {
+    return;
+  };
+  let Ok(msg_in) = RendezvousMessage::parse_from_bytes(&bytes) else {
+    return;
+  };
+  let rr = match msg_in.union {
+    Some(rendezvous_message::Union::RequestRelay(rr)) => rr,
+    Some(rendezvous_message::Union::Hc(hc)) => {
+      let mut msg_out = RendezvousMessage::new();
+      msg_out.set_hc(hc);
+      allow_err!(stream.send(&msg_out).await);
+      return;
+    }
+    _ => return,
+  };
+  if rr.uuid.is_empty() {
+    return;
+  }
+
+  // look up and wait under one lock, or both sides may miss each other
+  let mut peers = PEERS.lock().await;
+  if let Some((_, peer)) = peers.remove(&rr.uuid) {
+    drop(peers);
+    info!("Relay request {} got paired, from {}", rr.uuid, addr);
+    relay(stream, peer).await;
+    info!("Relay request {} closed", rr.uuid);
+  } else {
+    info!("New relay request {} from {}", rr.uuid, addr);
+    let seq = CONN_SEQ.fetch_add(1, Ordering::Relaxed);
+    peers.insert(rr.uuid.clone(), (seq, stream));
+    drop(peers);
+    sleep(Duration::from_millis(PAIR_TIMEOUT)).await;
+    let mut peers = PEERS.lock().await;
+    // the uuid may have been reused by a newer connection since
+    if peers.get(&rr.uuid).is_some_and(|(x, _)| *x == seq) {
+      peers.remove(&rr.uuid);
+      info!("Relay request {} timeout, no counterpart", rr.uuid);
+    }
+  }
+}
+
+/// Whether the relay server accepts a connection and answers a
+/// `HealthCheck` within `ms_timeout`, the port defaults to `RELAY_PORT`.
+pub async fn check_relay(relay_server: &str, ms_timeout: u64) -> bool {
+  let addr = check_port(relay_server, RELAY_PORT);
+  let mut stream = match FramedStream::new(&addr, None, ms_timeout).await {
+    Ok(stream) => stream,
+    Err(err) => {
+      debug!("relay server {} unreachable: {}", relay_server, err);
+      return false;
+    }
+  };
+  let token = HEALTH_CHECK_SEQ.fetch_add(1, Ordering::Relaxed).to_string();
+  let mut msg_out = RendezvousMessage::new();
+  msg_out.set_hc(HealthCheck {
+    token: token.clone(),
+    ..Default::default()
+  });
+  if let Err(err) = stream.send(&msg_out).await {
+    debug!("relay server {} unreachable: {}", relay_server, err);
+    return false;
+  }
+  match stream.next_timeout(ms_timeout).await {
+    Some(Ok(bytes)) => matches!(
+      RendezvousMessage::parse_from_bytes(&bytes).map(|msg| msg.union),
+      Ok(Some(rendezvous_message::Union::Hc(hc))) if hc.token == token
+    ),
+    _ => {
+      debug!(
+        "relay server {} did not answer the health check",
+        relay_server
+      );
+      false
+    }
+  }
+}
+
+/// Pipe the frames between two paired connections until either side closes.
+async fn relay(mut a: FramedStream, mut b: FramedStream) {
+  loop {
+    tokio::select! {
+      res = a.next() => match res {
+        Some(Ok(bytes)) => {
+          if b.send_bytes(bytes.into()).await.is_err() {
+            break;
+          }
+        }
+        _ => break,
+      },
+      res = b.next() => match res {
+        Some(Ok(bytes)) => {
+          if a.send_bytes(bytes.into()).await.is_err() {
+            break;
+          }
+        }
+        _ => break,
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use nimbus_common::{bytes::Bytes, protos::rendezvous::RequestRelay};
+
+  use super::*;
+
+  /// A relay server on a local port, returns its address.
+  async fn start_relay() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let relay_server = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+      while let Ok((stream, addr)) = listener.accept().await {
+        tokio::spawn(handle_connection(stream, addr));
+      }
+    });
+    relay_server
+  }
+
+  #[test]
+  fn test_check_relay() {
+    test_check_relay_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_check_relay_async() {
+    let relay_server = start_relay().await;
+    assert!(check_relay(&relay_server, 1_000).await);
+
+    // nothing listening any more
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let relay_server = listener.local_addr().unwrap().to_string();
+    drop(listener);
+    assert!(!check_relay(&relay_server, 1_000).await);
+  }
+
+  #[test]
+  fn test_relay() {
+    test_relay_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_relay_async() {
+    let relay_server = start_relay().await;
+    let mut msg_out = RendezvousMessage::new();
+    msg_out.set_request_relay(RequestRelay {
+      uuid: "test-relay".to_owned(),
+      ..Default::default()
+    });
+    // both sides connect at the same time
+    let connect = || async {
+      let mut stream =
+        FramedStream::new(&relay_server, None, 1_000).await.unwrap();
+      stream.send(&msg_out).await.unwrap();
+      stream
+    };
+    let (mut a, mut b) = tokio::join!(connect(), connect());
+
+    a.send_bytes(Bytes::from("ping")).await.unwrap();
+    let bytes = b.next_timeout(1_000).await.unwrap().unwrap();
+    assert_eq!(&bytes[..], b"ping");
+    b.send_bytes(Bytes::from("pong")).await.unwrap();
+    let bytes = a.next_timeout(1_000).await.unwrap().unwrap();
+    assert_eq!(&bytes[..], b"pong");
+    assert!(!PEERS.lock().await.contains_key("test-relay"));
+  }
+}
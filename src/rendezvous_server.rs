@@ -1,40 +1,58 @@
 use std::{
   collections::HashMap,
   net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
-  sync::Arc,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
   time::Duration,
 };
 
+mod janitor;
+mod nat_listener_handler;
+mod online_handler;
+mod port_listener_handler;
+mod punch_hole_handler;
+mod register_pk_handler;
+mod relay_handler;
+mod tcp_handler;
 mod test_nimbus;
 mod udp_handler;
-use udp_handler::*;
-mod port_listener_handler;
-use port_listener_handler::*;
-mod nat_listener_handler;
-use nat_listener_handler::*;
 mod ws_listener_handler;
-use ws_listener_handler::*;
-mod tcp_handler;
-use tcp_handler::*;
 
 use nimbus_common::{
+  allow_err,
   bytes::Bytes,
   bytes_codec::BytesCodec,
   config::SERIAL,
+  flexi_logger::LoggerHandle,
   futures::stream::SplitSink,
   logger::*,
-  tcp::listen_any,
+  protos::rendezvous::RendezvousMessage,
+  tcp::{listen_any, new_listener},
   tokio::{
     self,
     net::{TcpListener, TcpStream},
-    time::interval,
+    signal::unix::{signal, SignalKind},
+    sync::{mpsc, Mutex},
+    time::{interval, timeout},
   },
-  tokio_util::codec::Framed,
+  tokio_util::{codec::Framed, sync::CancellationToken, task::TaskTracker},
   udp::FramedSocket,
   ResultType,
 };
 
-use crate::peer::PeerMap;
+use crate::{
+  access_policy::AccessPolicy,
+  common::get_unix_time,
+  config::{Args, JanitorConfig, LimitsConfig, ServerConfig},
+  geo::Geo,
+  id_policy::IdPolicy,
+  peer::{PeerMap, IP_BLOCKER, IP_CHANGES},
+  peer_store::{self, bytes_to_guid, guid_to_bytes},
+  rate_limiter::Limiter,
+  server_key::ServerKey,
+};
 
 type TcpStreamSink = SplitSink<Framed<TcpStream, BytesCodec>, Bytes>;
 type RelayServers = Vec<String>;
@@ -42,16 +60,43 @@ type WsSink = SplitSink<
   tokio_tungstenite::WebSocketStream<TcpStream>,
   tungstenite::Message,
 >;
+type Sender = mpsc::UnboundedSender<Data>;
+type Receiver = mpsc::UnboundedReceiver<Data>;
+/// interval of the relay checks, and the timeout of a check (ms)
 static CHECK_RELAY_TIMEOUT: u64 = 3_000;
+/// max time to wait for the in-flight tcp/websocket handlers on shutdown (ms)
+static SHUTDOWN_TIMEOUT: u64 = 10_000;
+/// max entries listed by one admin command
+static CMD_LIST_SIZE: usize = 10;
+/// tell the peers to connect through a relay server instead of hole punching
+static ALWAYS_USE_RELAY: AtomicBool = AtomicBool::new(false);
+
+/// a tcp connection kept in `tcp_punch`, shared by the senders
+type SharedSink = Arc<Mutex<Sink>>;
 
 enum Sink {
   TcpStream(TcpStreamSink),
   Ws(WsSink),
 }
 
+/// Requests from the spawned tcp/websocket handlers to the `io_loop`,
+/// which owns the udp socket.
+enum Data {
+  /// send the message to the address by udp
+  Msg(Box<RendezvousMessage>, SocketAddr),
+  /// replace the relay servers, separated by `,`
+  RelayServers(String),
+  /// the relay servers which passed a round of checks, out of the
+  /// configured ones
+  ActiveRelayServers(Arc<RelayServers>, RelayServers),
+  /// the config reloaded on SIGHUP
+  Config(Box<ServerConfig>),
+}
+
 struct Inner {
-  serial: i32,
   local_ip: String,
+  /// signs the public keys of the peers sent to the clients
+  server_key: ServerKey,
 }
 
 #[derive(Clone)]
@@ -60,6 +105,29 @@ pub struct RendezvousServer {
   relay_servers: Arc<RelayServers>,
   relay_servers0: Arc<RelayServers>,
   rendezvous_servers: Arc<Vec<String>>,
+  /// serial of the config pushed to the clients, bumped when
+  /// `rendezvous_servers` changes
+  serial: i32,
+  limits: Arc<LimitsConfig>,
+  /// networks which can reach the listeners and the udp socket
+  access: Arc<AccessPolicy>,
+  /// rate limits of the requests, updated with `limits`
+  limiter: Arc<Limiter>,
+  /// TTLs of the entries in the in-memory maps
+  janitor: Arc<JanitorConfig>,
+  id_policy: Arc<IdPolicy>,
+  /// locates the peers to choose the closest relay server
+  geo: Arc<Geo>,
+  /// freshness window of `OnlineResponse` (ms)
+  online_timeout: u64,
+  /// tcp connections kept open by the peers, the messages to these addresses
+  /// are sent through them instead of udp, removed when closed
+  tcp_punch: Arc<Mutex<HashMap<SocketAddr, SharedSink>>>,
+  tx: Sender,
+  /// cancelled on SIGINT/SIGTERM
+  shutdown: CancellationToken,
+  /// the spawned tcp/websocket handlers
+  tasks: TaskTracker,
   inner: Arc<Inner>,
 }
 
@@ -68,13 +136,24 @@ enum LoopFailure {
   WsListener,
   NatListener,
   PortListener,
+  /// not a failure, the server is shutting down
+  Shutdown,
 }
 
 impl RendezvousServer {
+  /// Run the server until SIGINT/SIGTERM, the config is loaded again from
+  /// `args` on SIGHUP.
   #[tokio::main(flavor = "multi_thread")]
-  pub async fn start(port: i32, udp_recv_buffer_size: usize) -> ResultType<()> {
-    let nat_port = port - 1;
-    let ws_port = port + 2;
+  pub async fn start(
+    args: Args,
+    config: ServerConfig,
+    logger: LoggerHandle,
+  ) -> ResultType<()> {
+    let port = config.port;
+    let nat_port = config.nat_port();
+    let ws_port = config.ws_port();
+    let bind_addr = config.bind_addr;
+    let udp_recv_buffer_size = config.udp_recv_buffer_size;
     debug!(
       "Rendezvous start with nat_port={}, ws_port={}",
       nat_port, ws_port
@@ -85,26 +164,36 @@ impl RendezvousServer {
     info!("Listening on websocket: {}", ws_port);
 
     // udp socket
-    let mut udp_socket = crate_udp_listener(port, udp_recv_buffer_size).await?;
+    let mut udp_socket =
+      crate_udp_listener(bind_addr, port, udp_recv_buffer_size).await?;
 
     let local_ip = local_ip_address::local_ip()
       .map(|x| x.to_string())
       .unwrap_or_default();
 
-    let mut rendezvous_server = RendezvousServer {
-      inner: Arc::new(Inner {
-        local_ip,
-        serial: SERIAL,
-      }),
-      peer_map: PeerMap::new().await?,
-      relay_servers: Arc::new(vec![]),
-      relay_servers0: Arc::new(vec![]),
-      rendezvous_servers: Arc::new(vec![]),
+    let server_key = ServerKey::load_or_generate(&config.key_file)?;
+    info!("Key: {}", server_key.pk);
+    // the key the clients have to present, empty for any client
+    let key = match config.key.as_str() {
+      "-" => server_key.pk.clone(),
+      key => key.to_owned(),
     };
 
-    let mut port_listener = create_tcp_listener(port).await?;
-    let mut nat_listener = create_tcp_listener(nat_port).await?;
-    let mut ws_listener = create_tcp_listener(ws_port).await?;
+    info!("db-url={}", config.db_url);
+    let peer_store = peer_store::open(&config.db_url).await?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<Data>();
+    let mut rendezvous_server = RendezvousServer::new(
+      &config,
+      PeerMap::new(peer_store).await?,
+      server_key,
+      local_ip,
+      tx,
+    )?;
+
+    let mut port_listener = create_tcp_listener(bind_addr, port).await?;
+    let mut nat_listener = create_tcp_listener(bind_addr, nat_port).await?;
+    let mut ws_listener = create_tcp_listener(bind_addr, ws_port).await?;
 
     let test_addr = port_listener.local_addr()?;
     // test
@@ -127,6 +216,13 @@ impl RendezvousServer {
       }
     });
 
+    let shutdown = rendezvous_server.shutdown.clone();
+    let tasks = rendezvous_server.tasks.clone();
+    let peer_map = rendezvous_server.peer_map.clone();
+    let tx = rendezvous_server.tx.clone();
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let mut sighup = signal(SignalKind::hangup())?;
+
     let main_task = async move {
       loop {
         info!("main task start");
@@ -136,37 +232,120 @@ impl RendezvousServer {
             &mut nat_listener,
             &mut ws_listener,
             &mut udp_socket,
+            &mut rx,
+            &key,
           )
           .await
         {
           LoopFailure::UdpSocket => {
             debug!("LoopFailure UdpSocket");
             drop(udp_socket);
-            udp_socket = crate_udp_listener(port, udp_recv_buffer_size).await?;
+            udp_socket =
+              crate_udp_listener(bind_addr, port, udp_recv_buffer_size).await?;
           }
           LoopFailure::WsListener => {
             debug!("LoopFailure WebSocket listener");
             drop(ws_listener);
-            ws_listener = create_tcp_listener(port).await?;
+            ws_listener = create_tcp_listener(bind_addr, ws_port).await?;
           }
           LoopFailure::NatListener => {
             debug!("LoopFailure Nat listener");
             drop(nat_listener);
-            nat_listener = create_tcp_listener(port).await?;
+            nat_listener = create_tcp_listener(bind_addr, nat_port).await?;
           }
           LoopFailure::PortListener => {
             debug!("LoopFailure Port tcp listener");
             drop(port_listener);
-            port_listener = create_tcp_listener(port).await?;
+            port_listener = create_tcp_listener(bind_addr, port).await?;
+          }
+          LoopFailure::Shutdown => {
+            // the listeners are dropped here, stop accepting
+            return Ok(());
           }
         }
       }
     };
+    tokio::pin!(main_task);
 
-    // TODO: add signal listener
-    tokio::select! {
-      res = main_task => res,
+    loop {
+      tokio::select! {
+        res = &mut main_task => return res,
+        _ = tokio::signal::ctrl_c() => {
+          info!("SIGINT received");
+          break;
+        }
+        _ = sigterm.recv() => {
+          info!("SIGTERM received");
+          break;
+        }
+        _ = sighup.recv() => {
+          info!("SIGHUP received, reload the config");
+          match ServerConfig::load(&args) {
+            Ok(new_config) => {
+              if !config.same_listeners(&new_config) {
+                warn!("ports, bind address, database and key need a restart");
+              }
+              if let Err(err) = logger.parse_new_spec(&new_config.log_level) {
+                error!("invalid log level {}: {}", new_config.log_level, err);
+              }
+              tx.send(Data::Config(Box::new(new_config))).ok();
+            }
+            Err(err) => error!("failed to reload the config: {:?}", err),
+          }
+        }
+      }
+    }
+    shutdown.cancel();
+    main_task.await?;
+
+    tasks.close();
+    info!("waiting for {} connection handlers", tasks.len());
+    if timeout(Duration::from_millis(SHUTDOWN_TIMEOUT), tasks.wait())
+      .await
+      .is_err()
+    {
+      warn!("{} connection handlers aborted", tasks.len());
     }
+    if let Err(err) = peer_map.flush().await {
+      error!("failed to flush the peer store: {}", err);
+    }
+    info!("shutdown");
+    Ok(())
+  }
+
+  /// The server state of the config, without the sockets.
+  fn new(
+    config: &ServerConfig,
+    peer_map: PeerMap,
+    server_key: ServerKey,
+    local_ip: String,
+    tx: Sender,
+  ) -> ResultType<Self> {
+    let mut rendezvous_server = RendezvousServer {
+      inner: Arc::new(Inner {
+        local_ip,
+        server_key,
+      }),
+      peer_map,
+      relay_servers: Arc::new(vec![]),
+      relay_servers0: Arc::new(vec![]),
+      rendezvous_servers: Arc::new(config.rendezvous_servers.clone()),
+      serial: SERIAL,
+      limits: Arc::new(config.limits.clone()),
+      access: Arc::new(AccessPolicy::new(&config.access)?),
+      limiter: Arc::new(Limiter::new(&config.limits)),
+      janitor: Arc::new(config.janitor.clone()),
+      id_policy: Arc::new(IdPolicy::new(&config.id_policy)?),
+      geo: Arc::new(Geo::new(config)?),
+      online_timeout: config.online_timeout,
+      tcp_punch: Default::default(),
+      tx,
+      shutdown: CancellationToken::new(),
+      tasks: TaskTracker::new(),
+    };
+    rendezvous_server.parse_relay_servers(&config.relay_servers.join(","));
+    ALWAYS_USE_RELAY.store(config.always_use_relay, Ordering::SeqCst);
+    Ok(rendezvous_server)
   }
 
   async fn io_loop(
@@ -175,19 +354,48 @@ impl RendezvousServer {
     nat_listener: &mut TcpListener,
     ws_listener: &mut TcpListener,
     udp_socket: &mut FramedSocket,
+    rx: &mut Receiver,
+    key: &str,
   ) -> LoopFailure {
     let mut timer_check_relay =
       interval(Duration::from_millis(CHECK_RELAY_TIMEOUT));
+    let mut timer_janitor =
+      interval(Duration::from_secs(self.janitor.interval.max(1)));
     loop {
       tokio::select! {
+        _ = self.shutdown.cancelled() => {
+          return LoopFailure::Shutdown;
+        }
         _ = timer_check_relay.tick() => {
-          trace!("timer check relay");
+          self.check_relay_servers();
+        }
+        _ = timer_janitor.tick() => {
+          self.run_janitor();
+        }
+        Some(data) = rx.recv() => {
+          match data {
+            Data::Msg(msg, addr) => {
+              allow_err!(udp_socket.send(msg.as_ref(), addr).await);
+            }
+            Data::RelayServers(rs) => {
+              self.parse_relay_servers(&rs);
+            }
+            Data::ActiveRelayServers(rs0, rs) => {
+              self.update_active_relay_servers(rs0, rs);
+            }
+            Data::Config(config) => {
+              self.reload_config(&config);
+            }
+          }
         }
         res = port_listener.accept() => {
           match res {
+            Ok((_, addr)) if !self.access.check(addr.ip()) => {
+              debug!("tcp connection from {} denied", addr);
+            }
             Ok((stream, addr)) => {
               stream.set_nodelay(true).ok();
-              self.handle_port_listener(stream, addr, "").await;
+              self.handle_port_listener(stream, addr, key).await;
             }
             Err(err) => {
               error!("port listener accept failure: {}", err);
@@ -197,6 +405,9 @@ impl RendezvousServer {
         }
         res = nat_listener.accept() => {
           match res {
+            Ok((_, addr)) if !self.access.check(addr.ip()) => {
+              debug!("nat test connection from {} denied", addr);
+            }
             Ok((stream, addr)) => {
               stream.set_nodelay(true).ok();
               self.handle_nat_listener(stream, addr).await;
@@ -209,9 +420,12 @@ impl RendezvousServer {
         }
         res = ws_listener.accept() => {
           match res {
+            Ok((_, addr)) if !self.access.check(addr.ip()) => {
+              debug!("websocket connection from {} denied", addr);
+            }
             Ok((stream, addr)) => {
               stream.set_nodelay(true).ok();
-              self.handle_ws_listener(stream, addr, "").await;
+              self.handle_ws_listener(stream, addr, key).await;
             }
             Err(err) => {
               error!("websocket listener accept failure: {}", err);
@@ -222,7 +436,7 @@ impl RendezvousServer {
         res = udp_socket.next() => {
           match res {
             Some(Ok((bytes, addr))) => {
-              if let Err(err) = self.handle_udp(&bytes, addr.into(), udp_socket).await {
+              if let Err(err) = self.handle_udp(&bytes, addr.into(), udp_socket, key).await {
                 error!("udp failure: {}", err);
                 return LoopFailure::UdpSocket;
               }
@@ -240,28 +454,394 @@ impl RendezvousServer {
     }
   }
 
+  /// Replace the relay servers, separated by `,`.
+  fn parse_relay_servers(&mut self, relay_servers: &str) {
+    self.set_relay_servers(split_relay_servers(relay_servers));
+  }
+
+  /// Replace the relay servers, all active until the next check round.
+  fn set_relay_servers(&mut self, rs: RelayServers) {
+    info!("relay-servers={:?}", rs);
+    self.relay_servers0 = Arc::new(rs);
+    self.relay_servers = self.relay_servers0.clone();
+    relay_handler::NO_RELAY_ALIVE.store(false, Ordering::SeqCst);
+  }
+
+  /// Swap the reloadable settings, the handlers spawned from now on see the
+  /// new snapshots while the running ones keep the old.
+  fn reload_config(&mut self, config: &ServerConfig) {
+    // the check results are kept if the relays are the same
+    let rs = split_relay_servers(&config.relay_servers.join(","));
+    if *self.relay_servers0 != rs {
+      self.set_relay_servers(rs);
+    }
+    info!("rendezvous-servers={:?}", config.rendezvous_servers);
+    if *self.rendezvous_servers != config.rendezvous_servers {
+      // the clients fetch the new list with a newer serial
+      self.serial += 1;
+      info!("serial={}", self.serial);
+      self.rendezvous_servers = Arc::new(config.rendezvous_servers.clone());
+    }
+    info!("limits={:?}", config.limits);
+    self.limits = Arc::new(config.limits.clone());
+    self.limiter.update(&config.limits);
+    match AccessPolicy::new(&config.access) {
+      Ok(access) => {
+        info!("access={:?}", config.access);
+        self.access = Arc::new(access);
+      }
+      Err(err) => error!("keep the access policy: {:?}", err),
+    }
+    info!("janitor={:?}", config.janitor);
+    self.janitor = Arc::new(config.janitor.clone());
+    self.online_timeout = config.online_timeout;
+    info!("always-use-relay={}", config.always_use_relay);
+    ALWAYS_USE_RELAY.store(config.always_use_relay, Ordering::SeqCst);
+    match IdPolicy::new(&config.id_policy) {
+      Ok(id_policy) => {
+        info!("id-policy={:?}", config.id_policy);
+        self.id_policy = Arc::new(id_policy);
+      }
+      Err(err) => error!("keep the id policy: {:?}", err),
+    }
+    match Geo::new(config) {
+      Ok(geo) => {
+        info!(
+          "geo-db={}, relay-regions={:?}",
+          config.geo_db, config.relay_regions
+        );
+        self.geo = Arc::new(geo);
+      }
+      Err(err) => error!("keep the geo database: {:?}", err),
+    }
+  }
+
+  /// Admin commands from the loopback connections of the nat listener.
   async fn check_cmd(&self, cmd: &str) -> String {
     use std::fmt::Write as _;
 
     let mut res = "".to_owned();
     let mut fds = cmd.trim().split(' ');
     match fds.next() {
-      Some("h") => {}
-      Some("relay-servers" | "rs") => {}
-      Some("ip-blocker" | "ib") => {}
-      Some("ip-change" | "ic") => {}
-      Some("always-use-relay" | "aur") => {}
-      Some("test-geo" | "tg") => {}
+      Some("h") => {
+        res = format!(
+          "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n",
+          "relay-servers(rs) <separated by ,>",
+          "ip-blocker(ib) [<ip>|<number>] [-]",
+          "ip-change(ic) [<id> [-]|+<start>|-]",
+          "ip-churn(icf) [+<start>], the flagged ids of ic",
+          "always-use-relay(aur) [y|n] | <id> [y|n|-]",
+          "test-geo(tg) <ip> [<ip>]",
+          "access-list(al) [allow|deny <net> [-]]",
+          "device-status(ds) [<id>|#<guid> [disable|suspend <secs>|enable]]",
+        )
+      }
+      Some("relay-servers" | "rs") => {
+        if let Some(rs) = fds.next() {
+          self.tx.send(Data::RelayServers(rs.to_owned())).ok();
+        } else {
+          for ip in self.relay_servers.iter() {
+            let _ = writeln!(res, "{ip}");
+          }
+        }
+      }
+      Some("ip-blocker" | "ib") => {
+        let mut lock = IP_BLOCKER.lock().await;
+        let _ = writeln!(res, "{}", lock.len());
+        let ip = fds.next();
+        // either an ip or the start index of the listing
+        let mut start = ip.map(|x| x.parse::<usize>().ok()).unwrap_or(Some(0));
+        if start.is_none() {
+          if let Some(ip) = ip {
+            if let Some((ids, tm)) = lock.get(ip) {
+              let _ =
+                writeln!(res, "{}/{}s", ids.len(), tm.elapsed().as_secs());
+            }
+            if fds.next() == Some("-") {
+              lock.remove(ip);
+            }
+          }
+        } else if fds.next() == Some("-") {
+          lock.clear();
+          start = None;
+        }
+        if let Some(start) = start {
+          for (ip, (ids, tm)) in lock.iter().skip(start).take(CMD_LIST_SIZE) {
+            let _ = writeln!(
+              res,
+              "{}: {}/{}s",
+              ip,
+              ids.len(),
+              tm.elapsed().as_secs()
+            );
+          }
+        }
+      }
+      Some("ip-change" | "ic") => {
+        let mut lock = IP_CHANGES.lock().await;
+        let _ = writeln!(res, "{}", lock.len());
+        // `+<start>` pages the listing, ids are usually numeric
+        let start = match fds.next() {
+          None => Some(0),
+          Some("-") => {
+            lock.clear();
+            None
+          }
+          Some(x) if x.starts_with('+') => Some(x[1..].parse().unwrap_or(0)),
+          Some(id) => {
+            if let Some((tm, ips)) = lock.get(id) {
+              let _ = writeln!(res, "{}s {:?}", tm.elapsed().as_secs(), ips);
+            }
+            if fds.next() == Some("-") {
+              lock.remove(id);
+            }
+            None
+          }
+        };
+        if let Some(start) = start {
+          for (id, (tm, ips)) in lock.iter().skip(start).take(CMD_LIST_SIZE) {
+            let _ =
+              writeln!(res, "{}: {}s {:?}", id, tm.elapsed().as_secs(), ips);
+          }
+        }
+      }
+      Some("ip-churn" | "icf") => {
+        let lock = IP_CHANGES.lock().await;
+        let flagged: Vec<_> = lock
+          .iter()
+          .filter(|(_, (tm, ips))| self.is_churn(tm, ips.len()))
+          .collect();
+        let _ = writeln!(res, "{}", flagged.len());
+        let start = fds
+          .next()
+          .and_then(|x| x.strip_prefix('+')?.parse().ok())
+          .unwrap_or(0);
+        for (id, (tm, ips)) in flagged.iter().skip(start).take(CMD_LIST_SIZE) {
+          let _ = writeln!(
+            res,
+            "{}: {} ips/{}s {:?}",
+            id,
+            ips.len(),
+            tm.elapsed().as_secs(),
+            ips
+          );
+        }
+      }
+      Some("always-use-relay" | "aur") => {
+        match fds.next() {
+          Some(x) if x.eq_ignore_ascii_case("y") => {
+            ALWAYS_USE_RELAY.store(true, Ordering::SeqCst)
+          }
+          Some(x) if x.eq_ignore_ascii_case("n") => {
+            ALWAYS_USE_RELAY.store(false, Ordering::SeqCst)
+          }
+          Some(id) => {
+            // the setting of a peer, `-` to follow the server
+            let value = match fds.next() {
+              Some(x) if x.eq_ignore_ascii_case("y") => Some(Some(true)),
+              Some(x) if x.eq_ignore_ascii_case("n") => Some(Some(false)),
+              Some("-") => Some(None),
+              _ => None,
+            };
+            if let Some(value) = value {
+              match self.peer_map.set_always_use_relay(id, value).await {
+                Ok(true) => {}
+                Ok(false) => {
+                  let _ = writeln!(res, "{} not found", id);
+                  return res;
+                }
+                Err(err) => {
+                  let _ = writeln!(res, "failed to save {}: {}", id, err);
+                  return res;
+                }
+              }
+            }
+            match self.peer_map.get(id).await {
+              Some(peer) => {
+                let _ = writeln!(
+                  res,
+                  "{}: {:?}",
+                  id,
+                  peer.read().await.peer_info.always_use_relay
+                );
+              }
+              None => {
+                let _ = writeln!(res, "{} not found", id);
+              }
+            }
+            return res;
+          }
+          None => {}
+        }
+        let _ = writeln!(
+          res,
+          "ALWAYS_USE_RELAY: {:?}",
+          ALWAYS_USE_RELAY.load(Ordering::SeqCst)
+        );
+      }
+      Some("test-geo" | "tg") => {
+        let mut ips = Vec::new();
+        for x in fds {
+          match x.parse::<IpAddr>() {
+            Ok(ip) => {
+              let _ = writeln!(res, "{}: {:?}", ip, self.geo.lookup(ip));
+              ips.push(ip);
+            }
+            Err(_) => {
+              let _ = writeln!(res, "invalid ip {}", x);
+            }
+          }
+        }
+        if !ips.is_empty() {
+          let _ = writeln!(res, "relay: {}", self.get_relay_server(&ips));
+        }
+      }
+      Some("access-list" | "al") => {
+        if let Some(kind @ ("allow" | "deny")) = fds.next() {
+          let net = fds.next().map(|x| x.parse());
+          match net {
+            Some(Ok(net)) => {
+              let remove = fds.next() == Some("-");
+              if let Err(err) = self.access.update(kind == "deny", net, remove)
+              {
+                let _ = writeln!(res, "{}", err);
+              }
+            }
+            Some(Err(err)) => {
+              let _ = writeln!(res, "{}", err);
+              return res;
+            }
+            None => {}
+          }
+        }
+        for (deny, net, manual) in self.access.list() {
+          let _ = writeln!(
+            res,
+            "{} {}{}",
+            if deny { "deny" } else { "allow" },
+            net,
+            if manual { "" } else { " (config)" }
+          );
+        }
+      }
+      Some("device-status" | "ds") => {
+        let Some(device) = fds.next() else {
+          let disabled = self.peer_map.list_disabled().await;
+          let _ = writeln!(res, "{}", disabled.len());
+          for (guid, id, until) in disabled.iter().take(CMD_LIST_SIZE) {
+            let _ = writeln!(
+              res,
+              "{} #{}: {}",
+              id,
+              bytes_to_guid(guid),
+              disabled_status(*until)
+            );
+          }
+          return res;
+        };
+        // an id, or a guid of the peer store
+        let id = match device.strip_prefix('#') {
+          Some(guid) => {
+            let Ok(guid) = guid.parse::<i64>() else {
+              let _ = writeln!(res, "invalid guid {}", device);
+              return res;
+            };
+            match self.peer_map.id_of_guid(&guid_to_bytes(guid)).await {
+              Ok(Some(id)) => id,
+              Ok(None) => {
+                let _ = writeln!(res, "{} not found", device);
+                return res;
+              }
+              Err(err) => {
+                let _ = writeln!(res, "{}", err);
+                return res;
+              }
+            }
+          }
+          None => device.to_owned(),
+        };
+        let until = match fds.next() {
+          Some("disable") => Some(u64::MAX),
+          Some("suspend") => match fds.next().map(|x| x.parse::<u64>()) {
+            Some(Ok(secs)) => Some(get_unix_time().saturating_add(secs)),
+            _ => {
+              let _ = writeln!(res, "suspend <secs>");
+              return res;
+            }
+          },
+          Some("enable") => Some(0),
+          _ => None,
+        };
+        if let Some(until) = until {
+          match self.peer_map.set_disabled_until(&id, until).await {
+            Ok(true) => {
+              info!("{}: {}", id, disabled_status(until));
+            }
+            Ok(false) => {
+              let _ = writeln!(res, "{} not found", id);
+              return res;
+            }
+            Err(err) => {
+              let _ = writeln!(res, "failed to save {}: {}", id, err);
+              return res;
+            }
+          }
+        }
+        match self.peer_map.get(&id).await {
+          Some(peer) => {
+            let r = peer.read().await;
+            let status = if r.peer_info.is_disabled() {
+              disabled_status(r.peer_info.disabled_until)
+            } else {
+              "enabled".to_owned()
+            };
+            let _ =
+              writeln!(res, "{} #{}: {}", id, bytes_to_guid(&r.guid), status);
+          }
+          None => {
+            let _ = writeln!(res, "{} not found", id);
+          }
+        }
+      }
       _ => {}
     }
     res
   }
 }
 
+fn split_relay_servers(relay_servers: &str) -> RelayServers {
+  relay_servers
+    .split(',')
+    .map(|x| x.trim().to_owned())
+    .filter(|x| !x.is_empty())
+    .collect()
+}
+
+/// `disabled`, or how long a device is suspended for.
+fn disabled_status(until: u64) -> String {
+  if until == u64::MAX {
+    "disabled".to_owned()
+  } else if until == 0 {
+    "enabled".to_owned()
+  } else {
+    let secs = until.saturating_sub(get_unix_time());
+    format!("suspended for {}s", secs)
+  }
+}
+
 async fn crate_udp_listener(
+  bind_addr: Option<IpAddr>,
   port: i32,
   recv_buffer_size: usize,
 ) -> ResultType<FramedSocket> {
+  if let Some(ip) = bind_addr {
+    let addr = SocketAddr::new(ip, port as _);
+    info!("try to create udp FramedSocket on {}", addr);
+    let s = FramedSocket::new_reuse(&addr, false, recv_buffer_size).await?;
+    debug!("listen on udp {:?}", s.local_addr());
+    return Ok(s);
+  }
+
   let addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), port as _);
   info!("try to create udp FramedSocket on ipv6: {}", addr);
   if let Ok(s) = FramedSocket::new_reuse(&addr, false, recv_buffer_size).await {
@@ -277,8 +857,83 @@ async fn crate_udp_listener(
 }
 
 #[inline]
-async fn create_tcp_listener(port: i32) -> ResultType<TcpListener> {
-  let s = listen_any(port as _).await?;
+async fn create_tcp_listener(
+  bind_addr: Option<IpAddr>,
+  port: i32,
+) -> ResultType<TcpListener> {
+  let s = match bind_addr {
+    Some(ip) => new_listener(SocketAddr::new(ip, port as _), true).await?,
+    None => listen_any(port as _).await?,
+  };
   debug!("listen on tcp {:?}", s.local_addr());
   Ok(s)
 }
+
+#[cfg(test)]
+pub(super) mod tests {
+  use super::*;
+  use crate::config::AccessConfig;
+
+  /// A server on the peer store of the url, with the default config.
+  pub(super) async fn new_server(db_url: &str) -> RendezvousServer {
+    new_server_with_limits(db_url, Default::default()).await
+  }
+
+  pub(super) async fn new_server_with_limits(
+    db_url: &str,
+    limits: LimitsConfig,
+  ) -> RendezvousServer {
+    let store = peer_store::open(db_url).await.unwrap();
+    let peer_map = PeerMap::new(store).await.unwrap();
+    // the messages to the udp socket are dropped
+    let (tx, _) = mpsc::unbounded_channel::<Data>();
+    // nothing added from the console
+    let config = ServerConfig {
+      access: AccessConfig {
+        file: "".to_owned(),
+        ..Default::default()
+      },
+      limits,
+      ..Default::default()
+    };
+    RendezvousServer::new(
+      &config,
+      peer_map,
+      ServerKey::generate(),
+      "".to_owned(),
+      tx,
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn test_reload_relay_servers() {
+    test_reload_relay_servers_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_reload_relay_servers_async() {
+    let mut server = new_server("memory").await;
+    let mut config = ServerConfig {
+      relay_servers: vec!["a:21117".to_owned(), "b:21117".to_owned()],
+      access: AccessConfig {
+        file: "".to_owned(),
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    server.reload_config(&config);
+    assert_eq!(server.relay_servers.len(), 2);
+    let rs0 = server.relay_servers0.clone();
+    server.update_active_relay_servers(rs0, vec!["b:21117".to_owned()]);
+    assert_eq!(*server.relay_servers, vec!["b:21117"]);
+
+    // the same relays, the check results are kept
+    server.reload_config(&config);
+    assert_eq!(*server.relay_servers, vec!["b:21117"]);
+
+    config.relay_servers.push("c:21117".to_owned());
+    server.reload_config(&config);
+    assert_eq!(server.relay_servers.len(), 3);
+  }
+}
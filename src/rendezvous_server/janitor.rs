@@ -0,0 +1,65 @@
+use std::{
+  sync::atomic::{AtomicBool, Ordering},
+  time::Duration,
+};
+
+use nimbus_common::{logger::*, tokio};
+
+use crate::peer::{IP_BLOCKER, IP_CHANGES};
+
+use super::RendezvousServer;
+
+/// a sweep is running
+static SWEEPING: AtomicBool = AtomicBool::new(false);
+
+impl RendezvousServer {
+  /// Evict the expired entries of the in-memory maps in the background,
+  /// the TTLs are in `JanitorConfig`.
+  ///
+  /// A sweep is skipped while the previous one is still running.
+  pub(super) fn run_janitor(&self) {
+    if SWEEPING.swap(true, Ordering::SeqCst) {
+      return;
+    }
+    let rs = self.clone();
+    tokio::spawn(async move {
+      rs.sweep().await;
+      SWEEPING.store(false, Ordering::SeqCst);
+    });
+  }
+
+  async fn sweep(&self) {
+    let config = self.janitor.clone();
+    let ip_blocker = {
+      let mut lock = IP_BLOCKER.lock().await;
+      let n = lock.len();
+      lock.retain(|_, (_, tm)| tm.elapsed().as_secs() <= config.ip_blocker_ttl);
+      n - lock.len()
+    };
+    let ip_changes = {
+      let mut lock = IP_CHANGES.lock().await;
+      let n = lock.len();
+      lock.retain(|_, (tm, _)| tm.elapsed().as_secs() <= config.ip_changes_ttl);
+      n - lock.len()
+    };
+    let buckets = self.limiter.evict_idle();
+    let peer_ttl =
+      Some(Duration::from_secs(config.peer_ttl)).filter(|ttl| !ttl.is_zero());
+    let peers = self.peer_map.evict(peer_ttl).await;
+    let enabled = self.peer_map.expire_disabled().await;
+    if enabled > 0 {
+      info!(
+        "janitor enabled {} devices at the end of suspension",
+        enabled
+      );
+    }
+    if ip_blocker + ip_changes + buckets + peers > 0 {
+      info!(
+        "janitor evicted {} ip blocker, {} ip changes, {} rate limit and {} peer entries",
+        ip_blocker, ip_changes, buckets, peers
+      );
+    } else {
+      debug!("janitor evicted nothing");
+    }
+  }
+}
@@ -1,6 +1,7 @@
 use std::net::SocketAddr;
 
 use nimbus_common::{
+  logger::*,
   protobuf::Message,
   protos::rendezvous::{
     rendezvous_message, RendezvousMessage, TestNatResponse,
@@ -22,24 +23,32 @@ impl RendezvousServer {
     stream: TcpStream,
     addr: SocketAddr,
   ) {
-    let mut rs = self.clone();
+    let rs = self.clone();
     if addr.ip().is_loopback() {
-      tokio::spawn(async move {
+      self.tasks.spawn(async move {
         let mut stream = stream;
         let mut buffer = [0; 1024];
         if let Ok(Ok(n)) = timeout(1000, stream.read(&mut buffer[..])).await {
           if let Ok(data) = std::str::from_utf8(&buffer[..n]) {
             let res = rs.check_cmd(data).await;
-            stream.write(res.as_bytes()).await.ok();
+            stream.write_all(res.as_bytes()).await.ok();
           }
         }
       });
       return;
     }
+    if !self.limiter.check_ip(addr.ip()) {
+      debug!("rate limited, close the connection of {}", addr);
+      return;
+    }
     let stream = FramedStream::from(stream, addr);
-    tokio::spawn(async move {
+    self.tasks.spawn(async move {
       let mut stream = stream;
-      if let Some(Ok(bytes)) = stream.next_timeout(30_000).await {
+      let res = tokio::select! {
+        _ = rs.shutdown.cancelled() => return,
+        res = stream.next_timeout(30_000) => res,
+      };
+      if let Some(Ok(bytes)) = res {
         if let Ok(msg_in) = RendezvousMessage::parse_from_bytes(&bytes) {
           #[allow(clippy::single_match)]
           match msg_in.union {
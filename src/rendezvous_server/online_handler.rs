@@ -0,0 +1,63 @@
+use nimbus_common::protos::rendezvous::{OnlineResponse, RendezvousMessage};
+
+use super::RendezvousServer;
+
+impl RendezvousServer {
+  /// Presence of the peers, online if registered within `online_timeout`.
+  ///
+  /// Only the peers in memory are looked up, unknown ids are offline and
+  /// never added to the `PeerMap`. The disabled peers are offline too.
+  pub(super) async fn handle_online_request(
+    &self,
+    peers: &[String],
+  ) -> RendezvousMessage {
+    let mut online = Vec::with_capacity(peers.len());
+    for id in peers {
+      let is_online = match self.peer_map.get_in_memory(id).await {
+        Some(peer) => {
+          let r = peer.read().await;
+          let elapsed = r.last_register_time.elapsed();
+          (elapsed.as_millis() as u64) < self.online_timeout
+            && !r.peer_info.is_disabled()
+        }
+        None => false,
+      };
+      online.push(is_online);
+    }
+    let mut msg_out = RendezvousMessage::new();
+    msg_out.set_online_response(OnlineResponse {
+      states: online_states(&online).into(),
+      ..Default::default()
+    });
+    msg_out
+  }
+}
+
+/// Pack the states into bits, the most significant bit of the first byte
+/// is the first peer.
+fn online_states(online: &[bool]) -> Vec<u8> {
+  let mut states = vec![0u8; online.len().div_ceil(8)];
+  for (i, _) in online.iter().enumerate().filter(|(_, &x)| x) {
+    states[i / 8] |= 0x80 >> (i % 8);
+  }
+  states
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_online_states() {
+    assert!(online_states(&[]).is_empty());
+    assert_eq!(online_states(&[true]), vec![0x80]);
+    assert_eq!(
+      online_states(&[false, true, false, false, false, false, false, true]),
+      vec![0x41]
+    );
+    let mut online = vec![false; 9];
+    online[0] = true;
+    online[8] = true;
+    assert_eq!(online_states(&online), vec![0x80, 0x80]);
+  }
+}
@@ -25,7 +25,7 @@ impl RendezvousServer {
     debug!("Tcp connection from {:?}, port listener", addr);
     let mut rs = self.clone();
     let key = key.to_owned();
-    tokio::spawn(async move {
+    self.tasks.spawn(async move {
       allow_err!(rs.handle_port_listener_inner(stream, addr, &key).await)
     });
   }
@@ -41,13 +41,21 @@ impl RendezvousServer {
 
     let mut sink = Some(Sink::TcpStream(split_sink));
 
-    while let Ok(Some(Ok(bytes))) = timeout(30_000, split_stream.next()).await {
+    loop {
+      // the kept connections are closed on shutdown
+      let bytes = tokio::select! {
+        _ = self.shutdown.cancelled() => break,
+        res = timeout(30_000, split_stream.next()) => match res {
+          Ok(Some(Ok(bytes))) => bytes,
+          _ => break,
+        },
+      };
       if !self.handle_tcp(&bytes, &mut sink, addr, key, false).await {
         break;
       }
     }
     if sink.is_none() {
-      // TODO: tcp_punch remove addr
+      self.tcp_punch.lock().await.remove(&addr);
     }
 
     debug!("Tcp connection from {:?} closed, port listener", addr);
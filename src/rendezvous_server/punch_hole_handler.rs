@@ -0,0 +1,240 @@
+use std::{
+  net::{IpAddr, SocketAddr},
+  sync::atomic::Ordering,
+};
+
+use nimbus_common::{
+  addr_mangle::AddrMangle,
+  bytes::Bytes,
+  logger::*,
+  protos::rendezvous::{
+    punch_hole_response, FetchLocalAddr, LocalAddr, NatType, PunchHole,
+    PunchHoleRequest, PunchHoleResponse, PunchHoleSent, RendezvousMessage,
+  },
+};
+
+use crate::peer::REG_TIMEOUT;
+
+use super::{RendezvousServer, ALWAYS_USE_RELAY};
+
+impl RendezvousServer {
+  /// Look up the target peer of a `PunchHoleRequest`.
+  ///
+  /// If the server requires a key, the requester has to present it.
+  /// A websocket requester can neither punch holes nor connect in the lan,
+  /// the target is told to go through the relay, so is every target if
+  /// always-use-relay is on for it.
+  ///
+  /// If the target is online, returns the `PunchHole` message which should be
+  /// forwarded to the target address, carrying the requester's address,
+  /// or a `FetchLocalAddr` if both peers are in the same intranet.
+  /// Otherwise returns a failed `PunchHoleResponse` for the requester,
+  /// without a target address.
+  pub(super) async fn handle_punch_hole_request(
+    &self,
+    addr: SocketAddr,
+    ph: PunchHoleRequest,
+    key: &str,
+    ws: bool,
+  ) -> (RendezvousMessage, Option<SocketAddr>) {
+    if !key.is_empty() && ph.licence_key != key {
+      debug!("punch hole request from {} with a mismatched key", addr);
+      return (
+        punch_hole_failure(punch_hole_response::Failure::LICENSE_MISMATCH),
+        None,
+      );
+    }
+    let id = ph.id;
+    let peer_addr = match self.get_online_peer_addr(&id).await {
+      Ok(peer_addr) => peer_addr,
+      Err(failure) => {
+        debug!("punch hole request from {} to {}: {:?}", addr, id, failure);
+        return (punch_hole_failure(failure), None);
+      }
+    };
+
+    let peer_is_lan = is_lan(peer_addr);
+    let is_lan = is_lan(addr);
+    let mut relay_server = self.get_relay_server(&[addr.ip(), peer_addr.ip()]);
+    let mut nat_type = ph.nat_type;
+    let always_use_relay = self.always_use_relay(&id).await;
+    if always_use_relay {
+      nat_type = NatType::SYMMETRIC.into();
+    } else if ws || is_lan != peer_is_lan {
+      // neither a websocket nor the peer outside can punch a hole to the
+      // lan of the server, tell the target to go through the relay
+      if peer_is_lan {
+        relay_server = self.get_local_relay_server(&relay_server);
+      }
+      nat_type = NatType::SYMMETRIC.into();
+    }
+    let same_intranet = !ws
+      && !always_use_relay
+      && ((is_lan && peer_is_lan)
+        || addr.ip().to_canonical() == peer_addr.ip().to_canonical());
+
+    let socket_addr = AddrMangle::encode(addr).into();
+    let mut msg_out = RendezvousMessage::new();
+    if same_intranet {
+      debug!("fetch local addr from {} to {}({})", addr, id, peer_addr);
+      msg_out.set_fetch_local_addr(FetchLocalAddr {
+        socket_addr,
+        relay_server,
+        ..Default::default()
+      });
+    } else {
+      debug!("punch hole request from {} to {}({})", addr, id, peer_addr);
+      msg_out.set_punch_hole(PunchHole {
+        socket_addr,
+        nat_type,
+        relay_server,
+        ..Default::default()
+      });
+    }
+    (msg_out, Some(peer_addr))
+  }
+
+  /// Whether the peers connecting to `id` go through a relay server, the
+  /// setting of the peer overrides the server's.
+  pub(super) async fn always_use_relay(&self, id: &str) -> bool {
+    let always_use_relay = match self.peer_map.get(id).await {
+      Some(peer) => peer.read().await.peer_info.always_use_relay,
+      None => None,
+    };
+    always_use_relay.unwrap_or_else(|| ALWAYS_USE_RELAY.load(Ordering::SeqCst))
+  }
+
+  /// Find the last known address of an online peer.
+  pub(super) async fn get_online_peer_addr(
+    &self,
+    id: &str,
+  ) -> Result<SocketAddr, punch_hole_response::Failure> {
+    let Some(peer) = self.peer_map.get(id).await else {
+      return Err(punch_hole_response::Failure::ID_NOT_EXIST);
+    };
+    let r = peer.read().await;
+    if r.peer_info.is_disabled() {
+      return Err(punch_hole_response::Failure::DISABLED);
+    }
+    if r.last_register_time.elapsed().as_millis() >= REG_TIMEOUT as u128 {
+      return Err(punch_hole_response::Failure::OFFLINE);
+    }
+    Ok(r.socket_addr)
+  }
+
+  /// The target peer has received the `PunchHole` and started punching,
+  /// build the `PunchHoleResponse` for the requester with the target's
+  /// address, nat type and signed public key.
+  ///
+  /// If always-use-relay is on for the target, the requester is told to
+  /// go through the relay as well.
+  ///
+  /// Returns `None` if the requester address can not be decoded.
+  pub(super) async fn handle_hole_sent(
+    &self,
+    phs: PunchHoleSent,
+    addr: SocketAddr,
+  ) -> Option<(RendezvousMessage, SocketAddr)> {
+    let addr_a = AddrMangle::decode(&phs.socket_addr)?;
+    debug!(
+      "punch hole response to {} from {}({})",
+      addr_a, phs.id, addr
+    );
+    let pk = match self.peer_map.get(&phs.id).await {
+      Some(peer) => peer.read().await.pk.clone(),
+      None => Bytes::new(),
+    };
+    let pk = self.inner.server_key.sign_id_pk(&phs.id, pk);
+    let mut relay_server = phs.relay_server;
+    let mut nat_type = phs.nat_type;
+    if self.always_use_relay(&phs.id).await {
+      if relay_server.is_empty() {
+        relay_server = self.get_relay_server(&[addr.ip(), addr_a.ip()]);
+      }
+      nat_type = NatType::SYMMETRIC.into();
+    }
+    let mut msg_out = RendezvousMessage::new();
+    msg_out.set_punch_hole_response(PunchHoleResponse {
+      socket_addr: AddrMangle::encode(addr).into(),
+      pk,
+      relay_server,
+      nat_type,
+      ..Default::default()
+    });
+    Some((msg_out, addr_a))
+  }
+
+  /// The target in the same intranet has sent its lan address, build the
+  /// `PunchHoleResponse` for the requester to connect to it directly.
+  ///
+  /// Returns `None` if the requester address can not be decoded.
+  pub(super) async fn handle_local_addr(
+    &self,
+    la: LocalAddr,
+    addr: SocketAddr,
+  ) -> Option<(RendezvousMessage, SocketAddr)> {
+    let addr_a = AddrMangle::decode(&la.socket_addr)?;
+    debug!("local addr response to {} from {}({})", addr_a, la.id, addr);
+    let pk = match self.peer_map.get(&la.id).await {
+      Some(peer) => peer.read().await.pk.clone(),
+      None => Bytes::new(),
+    };
+    let pk = self.inner.server_key.sign_id_pk(&la.id, pk);
+    let mut msg_out = RendezvousMessage::new();
+    msg_out.set_punch_hole_response(PunchHoleResponse {
+      socket_addr: la.local_addr,
+      pk,
+      relay_server: la.relay_server,
+      is_local: true,
+      ..Default::default()
+    });
+    Some((msg_out, addr_a))
+  }
+}
+
+/// Whether the address is in a private network, i.e. the lan of the server.
+fn is_lan(addr: SocketAddr) -> bool {
+  match addr.ip().to_canonical() {
+    IpAddr::V4(ip) => ip.is_private() || ip.is_loopback() || ip.is_link_local(),
+    IpAddr::V6(ip) => {
+      // unique local fc00::/7 and link local fe80::/10
+      ip.is_loopback()
+        || (ip.segments()[0] & 0xfe00) == 0xfc00
+        || (ip.segments()[0] & 0xffc0) == 0xfe80
+    }
+  }
+}
+
+#[inline]
+fn punch_hole_failure(
+  failure: punch_hole_response::Failure,
+) -> RendezvousMessage {
+  let mut msg_out = RendezvousMessage::new();
+  msg_out.set_punch_hole_response(PunchHoleResponse {
+    failure: failure.into(),
+    ..Default::default()
+  });
+  msg_out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_is_lan() {
+    for addr in [
+      "192.168.1.2:1",
+      "10.0.0.1:1",
+      "127.0.0.1:1",
+      "[::ffff:172.16.0.1]:1",
+      "[fd00::1]:1",
+      "[fe80::1]:1",
+    ] {
+      assert!(is_lan(addr.parse().unwrap()), "{}", addr);
+    }
+    for addr in ["8.8.8.8:1", "[::ffff:8.8.8.8]:1", "[2001:db8::1]:1"] {
+      assert!(!is_lan(addr.parse().unwrap()), "{}", addr);
+    }
+  }
+}
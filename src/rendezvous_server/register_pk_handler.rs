@@ -0,0 +1,400 @@
+use std::{
+  collections::{HashMap, HashSet},
+  net::SocketAddr,
+  time::Instant,
+};
+
+use nimbus_common::{
+  logger::*,
+  protos::rendezvous::{
+    register_pk_response, RegisterPk, RegisterPkResponse, RendezvousMessage,
+  },
+};
+
+use crate::peer::{IpChangesMap, IP_BLOCKER, IP_CHANGES};
+
+use super::RendezvousServer;
+
+impl RendezvousServer {
+  /// message RegisterPk {
+  ///   string id = 1;
+  ///   bytes uuid = 2;
+  ///   bytes pk = 3; // public key
+  ///   string old_id = 4;
+  /// }
+  ///
+  /// Shared by udp, tcp and websocket, returns the result for the
+  /// `RegisterPkResponse`, or `None` if the message is ignored.
+  pub(super) async fn handle_register_pk(
+    &mut self,
+    rk: RegisterPk,
+    addr: SocketAddr,
+  ) -> Option<register_pk_response::Result> {
+    if rk.uuid.is_empty() || rk.pk.is_empty() {
+      return None;
+    }
+    let id = rk.id;
+    let ip = addr.ip().to_string();
+    if !self.id_policy.check(&id) {
+      return Some(register_pk_response::Result::INVALID_ID_FORMAT);
+    } else if !self.limiter.check_register(addr.ip(), &id)
+      || !self.check_ip_blocker(&ip, &id).await
+    {
+      // too frequent
+      return Some(register_pk_response::Result::TOO_FREQUENT);
+    } else if self.is_disabled(&id).await
+      || (!rk.old_id.is_empty() && self.is_disabled(&rk.old_id).await)
+    {
+      return Some(register_pk_response::Result::DISABLED);
+    }
+    let flagged = self.is_ip_churning(&id).await;
+    if flagged && self.limits.ip_change_reject {
+      return Some(register_pk_response::Result::TOO_FREQUENT);
+    }
+
+    // rename, the new id is `id`
+    if !rk.old_id.is_empty() && rk.old_id != id {
+      let res = self
+        .peer_map
+        .change_id(&rk.old_id, &id, &rk.uuid, &rk.pk)
+        .await;
+      if res == register_pk_response::Result::OK {
+        self.limiter.remove_id(&rk.old_id);
+      }
+      return Some(res);
+    }
+
+    // retrieves a peer based on id
+    // if the peer is new, return a new LockPeer
+    let peer = match self.peer_map.get_or(&id).await {
+      Ok(peer) => peer,
+      Err(err) => {
+        error!("failed to load peer {}: {}", id, err);
+        return Some(register_pk_response::Result::SERVER_ERROR);
+      }
+    };
+
+    let (changed, ip_changed) = {
+      let peer = peer.read().await;
+      if peer.uuid.is_empty() {
+        (true, false)
+      } else {
+        // whether the peer uuid, ip and public_key is same as the register_pk message
+        if peer.uuid == rk.uuid {
+          let strict = flagged && self.limits.ip_change_strict;
+          if (peer.peer_info.ip != ip || strict) && peer.pk != rk.pk {
+            warn!(
+              "Peer {} ip/pk mismatch: {}/{:?} vs {}/{:?}",
+              id, ip, rk.pk, peer.peer_info.ip, peer.pk
+            );
+            drop(peer);
+            return Some(register_pk_response::Result::UUID_MISMATCH);
+          }
+        } else {
+          warn!(
+            "Peer {} uuid mismatch: {:?} vs {:?}",
+            id, rk.uuid, peer.uuid
+          );
+          drop(peer);
+          return Some(register_pk_response::Result::UUID_MISMATCH);
+        }
+
+        // whether the peer address ip has changed
+        let ip_changed = peer.peer_info.ip != ip;
+        (
+          peer.uuid != rk.uuid || peer.pk != rk.pk || ip_changed,
+          ip_changed,
+        )
+      }
+    };
+
+    if ip_changed {
+      let n = track_ip_change(
+        &mut *IP_CHANGES.lock().await,
+        &id,
+        &ip,
+        self.limits.ip_change_duration,
+      );
+      let max = self.limits.ip_change_max;
+      if max > 0 && n > max {
+        if n == max + 1 {
+          warn!(
+            "Peer {} flagged, moved to {} ips within {}s, the last {}",
+            id, n, self.limits.ip_change_duration, ip
+          );
+        }
+        if self.limits.ip_change_reject {
+          return Some(register_pk_response::Result::TOO_FREQUENT);
+        }
+      }
+    }
+
+    // update the peer information
+    if changed {
+      let res = self
+        .peer_map
+        .update_pk(id, peer, addr, rk.uuid, rk.pk, ip)
+        .await;
+      if res != register_pk_response::Result::OK {
+        return Some(res);
+      }
+    } else {
+      // the same registration, e.g. of a peer loaded from the store
+      let mut w = peer.write().await;
+      w.socket_addr = addr;
+      w.last_register_time = Instant::now();
+    }
+
+    Some(register_pk_response::Result::OK)
+  }
+
+  /// Whether the peer of the id is disabled by the admin.
+  async fn is_disabled(&self, id: &str) -> bool {
+    match self.peer_map.get(id).await {
+      Some(peer) => peer.read().await.peer_info.is_disabled(),
+      None => false,
+    }
+  }
+
+  /// Whether the peer has moved to more than `ip_change_max` ips within
+  /// `ip_change_duration`.
+  pub(super) async fn is_ip_churning(&self, id: &str) -> bool {
+    match IP_CHANGES.lock().await.get(id) {
+      Some((tm, ips)) => self.is_churn(tm, ips.len()),
+      None => false,
+    }
+  }
+
+  pub(super) fn is_churn(&self, tm: &Instant, n: usize) -> bool {
+    let max = self.limits.ip_change_max;
+    max > 0
+      && n > max
+      && tm.elapsed().as_secs() <= self.limits.ip_change_duration
+  }
+
+  /// check if an IP address is blocked for registering too many ids, the
+  /// request rates are limited by `Limiter`
+  async fn check_ip_blocker(&self, ip: &str, id: &str) -> bool {
+    if self.limits.ip_ids_max == 0 {
+      return true;
+    }
+    // 1. required the IP_BLOCKER lock,
+    let mut lock = IP_BLOCKER.lock().await;
+    let now = Instant::now();
+    // check if the ip address exists in the `IP_BLOCKER` map
+    if let Some(counter) = lock.get_mut(ip) {
+      // 2. get the relevant id counter set (HashSet<id>, Instant)
+      let is_new = !counter.0.contains(id);
+      // elapsed `ip_ids_duration`, one day (3600 * 24) by default
+      if counter.1.elapsed().as_secs() > self.limits.ip_ids_duration {
+        counter.0.clear();
+      } else if counter.0.len() > self.limits.ip_ids_max {
+        // larger then limited(`ip_ids_max`, 300), if the Ip not the new one, it will be considered as blocked
+        return !is_new;
+      }
+
+      if is_new {
+        // added to the id counter set
+        counter.0.insert(id.to_owned());
+      }
+      // update the last visited time
+      counter.1 = now;
+    } else {
+      lock.insert(ip.to_owned(), (HashSet::from([id.to_owned()]), now));
+    }
+    true
+  }
+}
+
+/// The `RegisterPkResponse` of the result.
+#[inline]
+pub(super) fn register_pk_response(
+  res: register_pk_response::Result,
+) -> RendezvousMessage {
+  let mut msg_out = RendezvousMessage::new();
+  msg_out.set_register_pk_response(RegisterPkResponse {
+    result: res.into(),
+    ..Default::default()
+  });
+  msg_out
+}
+
+/// Record the new ip of a peer, the tracking restarts after `duration`
+/// seconds, returns the number of distinct ips since.
+fn track_ip_change(
+  changes: &mut IpChangesMap,
+  id: &str,
+  ip: &str,
+  duration: u64,
+) -> usize {
+  let (tm, ips) = changes
+    .entry(id.to_owned())
+    .or_insert_with(|| (Instant::now(), HashMap::new()));
+  if tm.elapsed().as_secs() > duration {
+    // the duration has passed, start over
+    *tm = Instant::now();
+    ips.clear();
+  }
+  *ips.entry(ip.to_owned()).or_insert(0) += 1;
+  ips.len()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use nimbus_common::{bytes::Bytes, tokio};
+
+  use super::*;
+  use crate::{
+    config::{BucketConfig, LimitsConfig},
+    rendezvous_server::tests::{new_server, new_server_with_limits},
+  };
+
+  /// `RegisterPk` of the id from the address.
+  async fn register(
+    server: &mut RendezvousServer,
+    addr: &str,
+    id: &str,
+    uuid: &str,
+    pk: &str,
+  ) -> Option<register_pk_response::Result> {
+    let rk = RegisterPk {
+      id: id.to_owned(),
+      uuid: Bytes::from(uuid.to_owned()),
+      pk: Bytes::from(pk.to_owned()),
+      ..Default::default()
+    };
+    server.handle_register_pk(rk, addr.parse().unwrap()).await
+  }
+
+  #[test]
+  fn test_track_ip_change() {
+    let mut changes = IpChangesMap::new();
+    assert_eq!(track_ip_change(&mut changes, "a", "1.1.1.1", 180), 1);
+    assert_eq!(track_ip_change(&mut changes, "a", "1.1.1.2", 180), 2);
+    assert_eq!(track_ip_change(&mut changes, "a", "1.1.1.1", 180), 2);
+    assert_eq!(changes["a"].1["1.1.1.1"], 2);
+    assert_eq!(track_ip_change(&mut changes, "b", "1.1.1.1", 180), 1);
+    // the duration has passed
+    changes.get_mut("a").unwrap().0 -= Duration::from_secs(181);
+    assert_eq!(track_ip_change(&mut changes, "a", "1.1.1.3", 180), 1);
+  }
+
+  #[test]
+  fn test_register_pk() {
+    test_register_pk_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_register_pk_async() {
+    use register_pk_response::Result::*;
+    let mut server = new_server("memory").await;
+    let addr = "3.3.3.1:1000";
+
+    let res = register(&mut server, addr, "520001", "u1", "").await;
+    assert_eq!(res, None);
+    let res = register(&mut server, addr, "abc", "u1", "p1").await;
+    assert_eq!(res, Some(INVALID_ID_FORMAT));
+    let res = register(&mut server, addr, "520001", "u1", "p1").await;
+    assert_eq!(res, Some(OK));
+    // the same registration again
+    let res = register(&mut server, addr, "520001", "u1", "p1").await;
+    assert_eq!(res, Some(OK));
+    let res = register(&mut server, addr, "520001", "u2", "p1").await;
+    assert_eq!(res, Some(UUID_MISMATCH));
+    // out of the tokens of the id
+    let res = register(&mut server, addr, "520001", "u1", "p1").await;
+    assert_eq!(res, Some(TOO_FREQUENT));
+    assert_eq!(
+      server.get_online_peer_addr("520001").await,
+      Ok(addr.parse().unwrap())
+    );
+
+    let res = register(&mut server, addr, "520002", "u1", "p1").await;
+    assert_eq!(res, Some(OK));
+    let peer = server.peer_map.get("520002").await.unwrap();
+    peer.write().await.peer_info.disabled_until = u64::MAX;
+    let res = register(&mut server, addr, "520002", "u1", "p1").await;
+    assert_eq!(res, Some(DISABLED));
+  }
+
+  /// Limits flagging a peer at its third ip change, with no limit on the
+  /// registrations of an id.
+  fn ip_change_limits() -> LimitsConfig {
+    LimitsConfig {
+      register_id: BucketConfig {
+        rate: 0.,
+        burst: 0.,
+      },
+      ip_change_max: 2,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn test_ip_change_reject() {
+    test_ip_change_reject_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_ip_change_reject_async() {
+    use register_pk_response::Result::*;
+    let limits = LimitsConfig {
+      ip_change_reject: true,
+      ..ip_change_limits()
+    };
+    let mut server = new_server_with_limits("memory", limits).await;
+    for ip in ["4.4.4.1", "4.4.4.2", "4.4.4.3"] {
+      let addr = format!("{}:1000", ip);
+      let res = register(&mut server, &addr, "540001", "u1", "p1").await;
+      assert_eq!(res, Some(OK));
+    }
+    let res = register(&mut server, "4.4.4.4:1000", "540001", "u1", "p1").await;
+    assert_eq!(res, Some(TOO_FREQUENT));
+    assert!(server.is_ip_churning("540001").await);
+    // flagged, even from a known ip
+    let res = register(&mut server, "4.4.4.1:1000", "540001", "u1", "p1").await;
+    assert_eq!(res, Some(TOO_FREQUENT));
+
+    // not flagged
+    let res = register(&mut server, "4.4.4.1:1000", "540002", "u1", "p1").await;
+    assert_eq!(res, Some(OK));
+    let res = register(&mut server, "4.4.4.2:1000", "540002", "u1", "p1").await;
+    assert_eq!(res, Some(OK));
+  }
+
+  #[test]
+  fn test_ip_change_strict() {
+    test_ip_change_strict_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_ip_change_strict_async() {
+    use register_pk_response::Result::*;
+    let limits = LimitsConfig {
+      ip_change_strict: true,
+      ..ip_change_limits()
+    };
+    let mut server = new_server_with_limits("memory", limits).await;
+    for ip in ["4.4.5.1", "4.4.5.2", "4.4.5.3", "4.4.5.4"] {
+      let addr = format!("{}:1000", ip);
+      let res = register(&mut server, &addr, "540003", "u1", "p1").await;
+      assert_eq!(res, Some(OK));
+    }
+    assert!(server.is_ip_churning("540003").await);
+    // flagged, a new key is refused even from the same ip
+    let res = register(&mut server, "4.4.5.4:1000", "540003", "u1", "p2").await;
+    assert_eq!(res, Some(UUID_MISMATCH));
+    let res = register(&mut server, "4.4.5.4:1000", "540003", "u1", "p1").await;
+    assert_eq!(res, Some(OK));
+
+    // not flagged, a new key is taken from the same ip
+    let res = register(&mut server, "4.4.5.1:1000", "540004", "u1", "p1").await;
+    assert_eq!(res, Some(OK));
+    let res = register(&mut server, "4.4.5.1:1000", "540004", "u1", "p2").await;
+    assert_eq!(res, Some(OK));
+    let peer = server.peer_map.get("540004").await.unwrap();
+    assert_eq!(peer.read().await.pk, "p2");
+  }
+}
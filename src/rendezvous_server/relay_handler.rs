@@ -0,0 +1,215 @@
+use std::{
+  net::{IpAddr, SocketAddr},
+  sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc,
+  },
+};
+
+use nimbus_common::{
+  addr_mangle::AddrMangle,
+  futures::future::join_all,
+  logger::*,
+  protos::rendezvous::{
+    punch_hole_response, RelayResponse, RendezvousMessage, RequestRelay,
+  },
+  tokio,
+};
+
+use super::{Data, RelayServers, RendezvousServer, CHECK_RELAY_TIMEOUT};
+use crate::relay_server::check_relay;
+
+/// round robin cursor over `relay_servers`
+static ROTATION_RELAY_SERVER: AtomicUsize = AtomicUsize::new(0);
+/// a round of relay checks is running
+static CHECKING_RELAY: AtomicBool = AtomicBool::new(false);
+/// no relay passed the last round, all the configured ones are handed out
+pub(super) static NO_RELAY_ALIVE: AtomicBool = AtomicBool::new(false);
+
+impl RendezvousServer {
+  /// Pick a relay server for the peers of `ips`, the closest one if they
+  /// are located, otherwise the next one in turn.
+  ///
+  /// Empty if no relay is configured.
+  pub(super) fn get_relay_server(&self, ips: &[IpAddr]) -> String {
+    if let Some(relay_server) = self.geo.closest_relay(&self.relay_servers, ips)
+    {
+      return relay_server.clone();
+    }
+    match self.relay_servers.len() {
+      0 => "".to_owned(),
+      1 => self.relay_servers[0].clone(),
+      n => {
+        let i = ROTATION_RELAY_SERVER.fetch_add(1, Ordering::SeqCst) % n;
+        self.relay_servers[i].clone()
+      }
+    }
+  }
+
+  /// Probe the configured relay servers in the background, the reachable
+  /// ones are sent back to the `io_loop` as `Data::ActiveRelayServers`.
+  ///
+  /// A round is skipped while the previous one is still running.
+  pub(super) fn check_relay_servers(&self) {
+    if self.relay_servers0.is_empty()
+      || CHECKING_RELAY.swap(true, Ordering::SeqCst)
+    {
+      return;
+    }
+    let relay_servers0 = self.relay_servers0.clone();
+    let tx = self.tx.clone();
+    tokio::spawn(async move {
+      let checks = relay_servers0
+        .iter()
+        .map(|relay_server| check_relay(relay_server, CHECK_RELAY_TIMEOUT));
+      let alive: RelayServers = relay_servers0
+        .iter()
+        .zip(join_all(checks).await)
+        .filter(|(_, ok)| *ok)
+        .map(|(relay_server, _)| relay_server.clone())
+        .collect();
+      tx.send(Data::ActiveRelayServers(relay_servers0, alive))
+        .ok();
+      CHECKING_RELAY.store(false, Ordering::SeqCst);
+    });
+  }
+
+  /// Apply a round of relay checks, the relays which failed are taken out
+  /// of `relay_servers` until they pass again.
+  ///
+  /// The result is dropped if the configured relays have changed since the
+  /// round started. If none passes, all the configured relays are kept.
+  pub(super) fn update_active_relay_servers(
+    &mut self,
+    relay_servers0: Arc<RelayServers>,
+    alive: RelayServers,
+  ) {
+    if !Arc::ptr_eq(&relay_servers0, &self.relay_servers0) {
+      return;
+    }
+    let no_relay_alive =
+      NO_RELAY_ALIVE.swap(alive.is_empty(), Ordering::SeqCst);
+    let previous: &[String] = if no_relay_alive {
+      &[]
+    } else {
+      &self.relay_servers
+    };
+    for relay_server in previous.iter() {
+      if !alive.contains(relay_server) {
+        warn!("relay server {} is down", relay_server);
+      }
+    }
+    for relay_server in alive.iter() {
+      if !previous.contains(relay_server) {
+        info!("relay server {} is back", relay_server);
+      }
+    }
+    if alive.is_empty() {
+      if !no_relay_alive {
+        warn!("no relay server is reachable, use all of them");
+      }
+      self.relay_servers = self.relay_servers0.clone();
+    } else if alive.len() == self.relay_servers0.len() {
+      self.relay_servers = self.relay_servers0.clone();
+    } else if alive != *self.relay_servers {
+      self.relay_servers = Arc::new(alive);
+    }
+  }
+
+  /// The relay server reached by the peers in the lan of the server, the
+  /// relay is supposed to run on this host with the same port.
+  pub(super) fn get_local_relay_server(&self, relay_server: &str) -> String {
+    let Ok(ip) = self.inner.local_ip.parse::<IpAddr>() else {
+      return relay_server.to_owned();
+    };
+    match relay_server
+      .rsplit_once(':')
+      .map(|(_, port)| port.parse::<u16>())
+    {
+      Some(Ok(port)) => SocketAddr::new(ip, port).to_string(),
+      _ => ip.to_string(),
+    }
+  }
+
+  /// Look up the target peer of a `RequestRelay`.
+  ///
+  /// If the server requires a key, the requester has to present it.
+  ///
+  /// If the target is online, returns the `RequestRelay` which should be
+  /// forwarded to the target address, carrying the requester's address
+  /// and the chosen relay server.
+  /// Otherwise returns a refused `RelayResponse` for the requester,
+  /// without a target address.
+  pub(super) async fn handle_request_relay(
+    &self,
+    addr: SocketAddr,
+    mut rr: RequestRelay,
+    key: &str,
+  ) -> (RendezvousMessage, Option<SocketAddr>) {
+    let mut msg_out = RendezvousMessage::new();
+    if !key.is_empty() && rr.licence_key != key {
+      debug!("relay request from {} with a mismatched key", addr);
+      msg_out.set_relay_response(RelayResponse {
+        uuid: rr.uuid,
+        refuse_reason: "Key mismatch".to_owned(),
+        ..Default::default()
+      });
+      return (msg_out, None);
+    }
+    let peer_addr = match self.get_online_peer_addr(&rr.id).await {
+      Ok(peer_addr) => peer_addr,
+      Err(failure) => {
+        debug!("relay request from {} to {}: {:?}", addr, rr.id, failure);
+        let refuse_reason = match failure {
+          punch_hole_response::Failure::OFFLINE => "Remote desktop is offline",
+          punch_hole_response::Failure::DISABLED => {
+            "Remote desktop is disabled"
+          }
+          _ => "ID does not exist",
+        };
+        msg_out.set_relay_response(RelayResponse {
+          uuid: rr.uuid,
+          refuse_reason: refuse_reason.to_owned(),
+          ..Default::default()
+        });
+        return (msg_out, None);
+      }
+    };
+
+    if rr.relay_server.is_empty() {
+      rr.relay_server = self.get_relay_server(&[addr.ip(), peer_addr.ip()]);
+    }
+    debug!(
+      "relay request {} from {} to {}({}) via {}",
+      rr.uuid, addr, rr.id, peer_addr, rr.relay_server
+    );
+    rr.socket_addr = AddrMangle::encode(addr).into();
+    // the key is only for the server
+    rr.licence_key.clear();
+    msg_out.set_request_relay(rr);
+    (msg_out, Some(peer_addr))
+  }
+
+  /// The target peer has connected to the relay server, build the
+  /// `RelayResponse` for the requester with the target's signed public key.
+  ///
+  /// Returns `None` if the requester address can not be decoded.
+  pub(super) async fn handle_relay_response(
+    &self,
+    mut rr: RelayResponse,
+  ) -> Option<(RendezvousMessage, SocketAddr)> {
+    let addr_a = AddrMangle::decode(&rr.socket_addr)?;
+    debug!(
+      "relay response {} to {} from {} via {}",
+      rr.uuid, addr_a, rr.id, rr.relay_server
+    );
+    rr.socket_addr = Default::default();
+    if let Some(peer) = self.peer_map.get(&rr.id).await {
+      let pk = peer.read().await.pk.clone();
+      rr.pk = self.inner.server_key.sign_id_pk(&rr.id, pk);
+    }
+    let mut msg_out = RendezvousMessage::new();
+    msg_out.set_relay_response(rr);
+    Some((msg_out, addr_a))
+  }
+}
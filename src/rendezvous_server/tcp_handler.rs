@@ -1,17 +1,21 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
 use nimbus_common::{
   allow_err,
   bytes::Bytes,
   futures::SinkExt,
+  logger::*,
   protobuf::Message,
   protos::rendezvous::{
-    register_pk_response, rendezvous_message, RegisterPkResponse,
-    RendezvousMessage, TestNatRequest, TestNatResponse,
+    register_pk_response, rendezvous_message, RendezvousMessage,
+    TestNatRequest, TestNatResponse,
   },
+  tokio::sync::Mutex,
 };
 
-use super::{RendezvousServer, Sink};
+use super::{
+  register_pk_handler::register_pk_response, Data, RendezvousServer, Sink,
+};
 
 impl RendezvousServer {
   #[inline]
@@ -24,17 +28,78 @@ impl RendezvousServer {
     is_websocket: bool,
   ) -> bool {
     if let Ok(msg_in) = RendezvousMessage::parse_from_bytes(bytes) {
+      if !self.limiter.check_message(addr.ip(), &msg_in) {
+        debug!("rate limited, close the connection of {}", addr);
+        return false;
+      }
       match msg_in.union {
         Some(rendezvous_message::Union::TestNatRequest(tar)) => {
           self.handle_test_nat_request(&tar, addr, sink).await
         }
-        Some(rendezvous_message::Union::RegisterPk(_)) => {
-          self.handle_tcp_register_pk(sink).await
+        Some(rendezvous_message::Union::RegisterPeer(rp)) => {
+          for msg_out in self.handle_register_peer(rp, addr).await {
+            self.reply(sink, addr, msg_out).await;
+          }
+          // the peer keeps the connection for the punch hole requests
+          self.keep_sink(sink, addr).await;
+        }
+        Some(rendezvous_message::Union::RegisterPk(rk)) => {
+          if let Some(res) = self.handle_register_pk(rk, addr).await {
+            self.reply(sink, addr, register_pk_response(res)).await;
+            if res == register_pk_response::Result::OK {
+              self.keep_sink(sink, addr).await;
+            }
+          }
+        }
+        Some(rendezvous_message::Union::OnlineRequest(or)) => {
+          let msg_out = self.handle_online_request(&or.peers).await;
+          self.reply(sink, addr, msg_out).await;
+        }
+        Some(rendezvous_message::Union::PunchHoleRequest(ph)) => {
+          // keep the connection for the punch hole response
+          self.keep_sink(sink, addr).await;
+          let (msg_out, peer_addr) = self
+            .handle_punch_hole_request(addr, ph, key, is_websocket)
+            .await;
+          self
+            .send_to_tcp_or_udp(msg_out, peer_addr.unwrap_or(addr))
+            .await;
+        }
+        Some(rendezvous_message::Union::PunchHoleSent(phs)) => {
+          if let Some((msg_out, addr_a)) =
+            self.handle_hole_sent(phs, addr).await
+          {
+            self.send_to_tcp_or_udp(msg_out, addr_a).await;
+          }
+        }
+        Some(rendezvous_message::Union::RequestRelay(rr)) => {
+          // keep the connection for the relay response,
+          // there maybe several attempts, so the sink can be none
+          self.keep_sink(sink, addr).await;
+          let (msg_out, peer_addr) =
+            self.handle_request_relay(addr, rr, key).await;
+          self
+            .send_to_tcp_or_udp(msg_out, peer_addr.unwrap_or(addr))
+            .await;
+        }
+        Some(rendezvous_message::Union::LocalAddr(la)) => {
+          if let Some((msg_out, addr_a)) =
+            self.handle_local_addr(la, addr).await
+          {
+            self.send_to_tcp_or_udp(msg_out, addr_a).await;
+          }
+        }
+        Some(rendezvous_message::Union::RelayResponse(rr)) => {
+          if let Some((msg_out, addr_a)) = self.handle_relay_response(rr).await
+          {
+            self.send_to_tcp_or_udp(msg_out, addr_a).await;
+          }
         }
         _ => {}
       }
     }
-    false
+    // read on while the connection is kept
+    sink.is_none()
   }
 
   pub(super) async fn handle_test_nat_request(
@@ -48,39 +113,148 @@ impl RendezvousServer {
       port: addr.port() as _,
       ..Default::default()
     };
-    if self.inner.serial > tar.serial {
+    if self.serial > tar.serial {
       // config update
     }
     msg_out.set_test_nat_response(res);
     Self::send_to_sink(sink, msg_out).await;
   }
 
-  pub(super) async fn handle_tcp_register_pk(
-    &mut self,
+  /// Keep the connection in `tcp_punch`, the messages to the address are
+  /// sent through it until it is closed.
+  async fn keep_sink(&self, sink: &mut Option<Sink>, addr: SocketAddr) {
+    if let Some(sink) = sink.take() {
+      self
+        .tcp_punch
+        .lock()
+        .await
+        .insert(addr, Arc::new(Mutex::new(sink)));
+    }
+  }
+
+  /// Reply to the tcp connection, which may be kept in `tcp_punch` already.
+  async fn reply(
+    &self,
     sink: &mut Option<Sink>,
+    addr: SocketAddr,
+    msg: RendezvousMessage,
   ) {
-    let res = register_pk_response::Result::NOT_SUPPORT;
-    let mut msg_out = RendezvousMessage::new();
-    msg_out.set_register_pk_response(RegisterPkResponse {
-      result: res.into(),
-      ..Default::default()
-    });
-    Self::send_to_sink(sink, msg_out).await;
+    if sink.is_some() {
+      Self::send_to_sink(sink, msg).await;
+    } else {
+      self.send_to_tcp_or_udp(msg, addr).await;
+    }
+  }
+
+  /// Send the message through the kept tcp connection of the address,
+  /// or by udp if there is none.
+  pub(super) async fn send_to_tcp_or_udp(
+    &self,
+    msg: RendezvousMessage,
+    addr: SocketAddr,
+  ) {
+    // not to hold the map while sending
+    let sink = self.tcp_punch.lock().await.get(&addr).cloned();
+    if let Some(sink) = sink {
+      Self::write_to_sink(&mut *sink.lock().await, msg).await;
+    } else {
+      self.tx.send(Data::Msg(msg.into(), addr)).ok();
+    }
   }
 
   #[inline]
   async fn send_to_sink(sink: &mut Option<Sink>, msg: RendezvousMessage) {
     if let Some(sink) = sink.as_mut() {
-      if let Ok(bytes) = msg.write_to_bytes() {
-        match sink {
-          Sink::TcpStream(s) => {
-            allow_err!(s.send(Bytes::from(bytes)).await)
-          }
-          Sink::Ws(ws) => {
-            allow_err!(ws.send(tungstenite::Message::Binary(bytes)).await)
-          }
+      Self::write_to_sink(sink, msg).await;
+    }
+  }
+
+  #[inline]
+  async fn write_to_sink(sink: &mut Sink, msg: RendezvousMessage) {
+    if let Ok(bytes) = msg.write_to_bytes() {
+      match sink {
+        Sink::TcpStream(s) => {
+          allow_err!(s.send(Bytes::from(bytes)).await)
+        }
+        Sink::Ws(ws) => {
+          allow_err!(ws.send(tungstenite::Message::Binary(bytes)).await)
         }
       }
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use nimbus_common::{
+    bytes_codec::BytesCodec,
+    futures::StreamExt,
+    protos::rendezvous::RegisterPk,
+    tokio::{
+      self,
+      net::{TcpListener, TcpStream},
+    },
+    tokio_util::codec::Framed,
+  };
+
+  use super::*;
+  use crate::rendezvous_server::tests::new_server;
+
+  /// The result of the `RegisterPkResponse` read by the client.
+  async fn read_result(
+    client: &mut Framed<TcpStream, BytesCodec>,
+  ) -> register_pk_response::Result {
+    let bytes = client.next().await.unwrap().unwrap();
+    let msg = RendezvousMessage::parse_from_bytes(&bytes).unwrap();
+    msg.register_pk_response().result.enum_value().unwrap()
+  }
+
+  #[test]
+  fn test_register_pk_keep_sink() {
+    test_register_pk_keep_sink_async();
+  }
+
+  #[tokio::main(flavor = "current_thread")]
+  async fn test_register_pk_keep_sink_async() {
+    let mut server = new_server("memory").await;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let client = TcpStream::connect(listener.local_addr().unwrap());
+    let (client, accepted) = tokio::join!(client, listener.accept());
+    let mut client = Framed::new(client.unwrap(), BytesCodec::new());
+    let (stream, addr) = accepted.unwrap();
+    let (sink, _) = Framed::new(stream, BytesCodec::new()).split();
+    let mut sink = Some(Sink::TcpStream(sink));
+    let register = |id: &str| {
+      let mut msg = RendezvousMessage::new();
+      msg.set_register_pk(RegisterPk {
+        id: id.to_owned(),
+        uuid: Bytes::from("u1"),
+        pk: Bytes::from("p1"),
+        ..Default::default()
+      });
+      msg.write_to_bytes().unwrap()
+    };
+
+    // refused, the connection is closed
+    let read_on = server
+      .handle_tcp(&register("abc"), &mut sink, addr, "", false)
+      .await;
+    assert!(!read_on && sink.is_some());
+    assert_eq!(
+      read_result(&mut client).await,
+      register_pk_response::Result::INVALID_ID_FORMAT
+    );
+    assert!(server.tcp_punch.lock().await.is_empty());
+
+    // registered, the connection is kept for the punch hole requests
+    let read_on = server
+      .handle_tcp(&register("530001"), &mut sink, addr, "", false)
+      .await;
+    assert!(read_on && sink.is_none());
+    assert_eq!(
+      read_result(&mut client).await,
+      register_pk_response::Result::OK
+    );
+    assert!(server.tcp_punch.lock().await.contains_key(&addr));
+  }
+}
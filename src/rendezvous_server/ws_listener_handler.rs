@@ -1,11 +1,12 @@
 use std::net::SocketAddr;
 
 use nimbus_common::{
+  allow_err,
   futures::StreamExt,
   logger::*,
   timeout,
   tokio::{self, net::TcpStream},
-  ResultType, allow_err,
+  ResultType,
 };
 
 use super::{RendezvousServer, Sink};
@@ -20,7 +21,7 @@ impl RendezvousServer {
     debug!("Tcp connection from {:?}, websocket listener", addr);
     let mut rs = self.clone();
     let key = key.to_owned();
-    tokio::spawn(async move {
+    self.tasks.spawn(async move {
       allow_err!(rs.handle_ws_listener_inner(stream, addr, &key).await);
     });
   }
@@ -32,21 +33,32 @@ impl RendezvousServer {
     addr: SocketAddr,
     key: &str,
   ) -> ResultType<()> {
-    let ws_stream = tokio_tungstenite::accept_async(stream).await?;
+    let ws_stream = tokio::select! {
+      _ = self.shutdown.cancelled() => return Ok(()),
+      res = timeout(30_000, tokio_tungstenite::accept_async(stream)) => res??,
+    };
 
     let (split_sink, mut split_stream) = ws_stream.split();
 
     let mut sink = Some(Sink::Ws(split_sink));
-    while let Ok(Some(Ok(msg))) = timeout(30_000, split_stream.next()).await {
+    loop {
+      // the kept connections are closed on shutdown
+      let msg = tokio::select! {
+        _ = self.shutdown.cancelled() => break,
+        res = timeout(30_000, split_stream.next()) => match res {
+          Ok(Some(Ok(msg))) => msg,
+          _ => break,
+        },
+      };
       if let tungstenite::Message::Binary(bytes) = msg {
-        if !self.handle_tcp(&bytes, &mut sink, addr, key, false).await {
+        if !self.handle_tcp(&bytes, &mut sink, addr, key, true).await {
           break;
         }
       }
     }
 
     if sink.is_none() {
-      // TODO: tcp_punch remove addr
+      self.tcp_punch.lock().await.remove(&addr);
     }
 
     debug!("Tcp connection from {:?} closed, websocket listener", addr);
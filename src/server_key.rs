@@ -0,0 +1,136 @@
+use std::{io::Write, path::Path};
+
+use nimbus_common::{
+  anyhow::{anyhow, bail, Context},
+  bytes::Bytes,
+  logger::*,
+  protobuf::Message,
+  protos::rendezvous::IdPk,
+  sodiumoxide::{
+    base64::{self, Variant},
+    crypto::sign,
+  },
+  ResultType,
+};
+
+/// The long-term Ed25519 key pair identifying the server.
+///
+/// The secret key is kept in `<key_file>` and the public key in
+/// `<key_file>.pub`, both in base64.
+#[derive(Clone)]
+pub struct ServerKey {
+  /// public key in base64, the key the clients are configured with
+  pub pk: String,
+  sk: sign::SecretKey,
+}
+
+impl ServerKey {
+  /// Load the key pair from the key file, or generate and save a new one on
+  /// first start.
+  pub fn load_or_generate(key_file: &str) -> ResultType<ServerKey> {
+    nimbus_common::sodiumoxide::init()
+      .map_err(|_| anyhow!("failed to initialize sodiumoxide"))?;
+    let path = Path::new(key_file);
+    let pub_path = format!("{}.pub", key_file);
+    if path.exists() {
+      let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", key_file))?;
+      let sk = base64::decode(content.trim(), Variant::Original)
+        .ok()
+        .and_then(|x| sign::SecretKey::from_slice(&x))
+        .ok_or_else(|| anyhow!("invalid secret key in {}", key_file))?;
+      let key = ServerKey::from_sk(sk);
+      match std::fs::read_to_string(&pub_path) {
+        Ok(pk) if pk.trim() == key.pk => {}
+        Ok(_) => bail!("{} does not match {}", pub_path, key_file),
+        Err(_) => std::fs::write(&pub_path, &key.pk)?,
+      }
+      info!("server key loaded from {}", key_file);
+      return Ok(key);
+    }
+
+    let key = ServerKey::generate();
+    write_secret(path, &base64::encode(&key.sk, Variant::Original))
+      .with_context(|| format!("failed to write {}", key_file))?;
+    std::fs::write(&pub_path, &key.pk)
+      .with_context(|| format!("failed to write {}", pub_path))?;
+    info!("new server key saved to {}", key_file);
+    Ok(key)
+  }
+
+  /// A new random key pair.
+  pub(crate) fn generate() -> ServerKey {
+    let (_, sk) = sign::gen_keypair();
+    ServerKey::from_sk(sk)
+  }
+
+  fn from_sk(sk: sign::SecretKey) -> ServerKey {
+    let pk = base64::encode(sk.public_key(), Variant::Original);
+    ServerKey { pk, sk }
+  }
+
+  /// Sign the public key of a peer along with its id, so the clients can
+  /// check it with the server's public key.
+  ///
+  /// An unknown peer has no public key, then nothing is signed.
+  pub fn sign_id_pk(&self, id: &str, pk: Bytes) -> Bytes {
+    if pk.is_empty() {
+      return pk;
+    }
+    let id_pk = IdPk {
+      id: id.to_owned(),
+      pk,
+      ..Default::default()
+    };
+    match id_pk.write_to_bytes() {
+      Ok(data) => sign::sign(&data, &self.sk).into(),
+      Err(err) => {
+        error!("failed to encode IdPk of {}: {}", id, err);
+        Bytes::new()
+      }
+    }
+  }
+}
+
+/// The secret key is only readable by the owner.
+fn write_secret(path: &Path, content: &str) -> std::io::Result<()> {
+  let mut options = std::fs::OpenOptions::new();
+  options.write(true).create(true).truncate(true);
+  #[cfg(unix)]
+  std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+  options.open(path)?.write_all(content.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::peer_store::tests::temp_path;
+
+  #[test]
+  fn test_server_key() {
+    let key_file = temp_path("id_ed25519");
+    let pub_file = temp_path("id_ed25519.pub");
+
+    let key = ServerKey::load_or_generate(&key_file).unwrap();
+    assert_eq!(std::fs::read_to_string(&pub_file).unwrap(), key.pk);
+    // the same key on later starts
+    let key2 = ServerKey::load_or_generate(&key_file).unwrap();
+    assert_eq!(key.pk, key2.pk);
+
+    let signed = key.sign_id_pk("123456", Bytes::from_static(b"pk"));
+    let pk = base64::decode(&key.pk, Variant::Original).unwrap();
+    let pk = sign::PublicKey::from_slice(&pk).unwrap();
+    let id_pk =
+      IdPk::parse_from_bytes(&sign::verify(&signed, &pk).unwrap()).unwrap();
+    assert_eq!(id_pk.id, "123456");
+    assert_eq!(&id_pk.pk[..], b"pk");
+    assert!(key.sign_id_pk("123456", Bytes::new()).is_empty());
+
+    // a public key file of another key is refused
+    std::fs::write(&pub_file, "x").unwrap();
+    assert!(ServerKey::load_or_generate(&key_file).is_err());
+
+    std::fs::remove_file(&key_file).ok();
+    std::fs::remove_file(&pub_file).ok();
+  }
+}